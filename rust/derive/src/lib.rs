@@ -0,0 +1,121 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Derive macros for the [`scopedb-client`](https://docs.rs/scopedb-client) crate.
+//!
+//! Use them through the `derive` feature of `scopedb-client` rather than depending on this crate
+//! directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::LitStr;
+use syn::parse_macro_input;
+
+/// Derive `FromRow` for a struct with named fields, mapping each column to the field of the same
+/// name.
+///
+/// Columns that are not mapped to a field are ignored, while a field without a matching column
+/// fails the conversion. Declare a field as `Option<T>` to accept `NULL` values. Rename a field
+/// with `#[scopedb(rename = "column")]`.
+#[proc_macro_derive(FromRow, attributes(scopedb))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_row(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_from_row(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "FromRow can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "FromRow can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut initializers = Vec::with_capacity(fields.len());
+    let mut columns = Vec::with_capacity(fields.len());
+    for (i, field) in fields.iter().enumerate() {
+        let ident = field
+            .ident
+            .as_ref()
+            .expect("named fields must have an ident");
+        let mut column = ident.to_string().trim_start_matches("r#").to_string();
+        for attr in &field.attrs {
+            if !attr.path().is_ident("scopedb") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    column = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported scopedb attribute"))
+                }
+            })?;
+        }
+        initializers.push(quote! {
+            #ident: columns.take(&mut row, #i)?
+        });
+        columns.push(column);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::scopedb_client::FromRow for #name #ty_generics #where_clause {
+            fn from_row(
+                schema: &::scopedb_client::Schema,
+                row: ::std::vec::Vec<::scopedb_client::Value>,
+            ) -> ::std::result::Result<Self, ::scopedb_client::Error> {
+                let mut rows = Self::from_rows(schema, ::std::vec![row])?;
+                ::std::result::Result::Ok(rows.remove(0))
+            }
+
+            fn from_rows(
+                schema: &::scopedb_client::Schema,
+                rows: ::std::vec::Vec<::std::vec::Vec<::scopedb_client::Value>>,
+            ) -> ::std::result::Result<
+                ::std::vec::Vec<Self>,
+                ::scopedb_client::Error,
+            > {
+                let columns =
+                    ::scopedb_client::__private::ColumnPositions::new(schema, &[#(#columns),*])?;
+                rows.into_iter()
+                    .map(|mut row| {
+                        columns.check_row(&row)?;
+                        ::std::result::Result::Ok(Self {
+                            #(#initializers,)*
+                        })
+                    })
+                    .collect()
+            }
+        }
+    })
+}
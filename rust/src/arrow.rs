@@ -0,0 +1,727 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding of result sets returned in the Arrow IPC format, and encoding of ingest data in it.
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use arrow_array::Array;
+use arrow_array::ArrayRef;
+use arrow_array::BinaryArray;
+use arrow_array::BooleanArray;
+use arrow_array::Decimal128Array;
+use arrow_array::Float64Array;
+use arrow_array::Int64Array;
+use arrow_array::IntervalMonthDayNanoArray;
+use arrow_array::NullArray;
+use arrow_array::RecordBatch;
+use arrow_array::RecordBatchOptions;
+use arrow_array::StringArray;
+use arrow_array::TimestampMicrosecondArray;
+use arrow_array::UInt64Array;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Decimal128Type;
+use arrow_array::types::DurationMicrosecondType;
+use arrow_array::types::DurationMillisecondType;
+use arrow_array::types::DurationNanosecondType;
+use arrow_array::types::DurationSecondType;
+use arrow_array::types::Float64Type;
+use arrow_array::types::Int64Type;
+use arrow_array::types::IntervalMonthDayNano;
+use arrow_array::types::IntervalMonthDayNanoType;
+use arrow_array::types::TimestampMicrosecondType;
+use arrow_array::types::TimestampMillisecondType;
+use arrow_array::types::TimestampNanosecondType;
+use arrow_array::types::TimestampSecondType;
+use arrow_array::types::UInt64Type;
+use arrow_cast::display::ArrayFormatter;
+use arrow_cast::display::FormatOptions;
+use arrow_ipc::reader::StreamReader;
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::ArrowError;
+use arrow_schema::DataType as ArrowDataType;
+use arrow_schema::Field;
+use arrow_schema::IntervalUnit;
+use arrow_schema::Schema as ArrowSchema;
+use arrow_schema::TimeUnit;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::DataType;
+use crate::Error;
+use crate::ErrorKind;
+use crate::FieldSchema;
+use crate::Schema;
+use crate::Value;
+use crate::result::column_cells;
+use crate::result::decimal_mantissas;
+use crate::result::decode_binary;
+use crate::result::parse_cell;
+
+/// Decode the base64-encoded Arrow IPC stream of a result set.
+pub(crate) fn decode_batches(rows: &str) -> Result<Vec<RecordBatch>, Error> {
+    let payload = STANDARD.decode(rows).map_err(|err| {
+        Error::new(
+            ErrorKind::Unexpected,
+            "failed to decode arrow result set".to_string(),
+        )
+        .set_source(err)
+    })?;
+
+    let reader = StreamReader::try_new(Cursor::new(payload), None).map_err(decode_error)?;
+    reader.collect::<Result<Vec<_>, _>>().map_err(decode_error)
+}
+
+/// Encode record batches, which must share a schema, as a base64-encoded Arrow IPC stream.
+pub(crate) fn encode_batches(batches: &[RecordBatch]) -> Result<String, Error> {
+    let encode_error = |err: ArrowError| {
+        Error::new(
+            ErrorKind::Unexpected,
+            "failed to encode arrow ingest data".to_string(),
+        )
+        .set_source(err)
+    };
+
+    let Some(first) = batches.first() else {
+        return Err(Error::new(
+            ErrorKind::Unexpected,
+            "no record batches to ingest".to_string(),
+        ));
+    };
+    let mut writer = StreamWriter::try_new(vec![], &first.schema()).map_err(encode_error)?;
+    for batch in batches {
+        writer.write(batch).map_err(encode_error)?;
+    }
+    let payload = writer.into_inner().map_err(encode_error)?;
+    Ok(STANDARD.encode(payload))
+}
+
+/// Re-encode a base64-encoded Arrow IPC stream of ingest data as multiple streams that take at
+/// most `max_bytes` each, except for single rows that are larger.
+pub(crate) fn split_encoded_batches(rows: &str, max_bytes: usize) -> Result<Vec<String>, Error> {
+    let batches = decode_batches(rows)?;
+    let mut chunks = vec![];
+    split_batches(batches, max_bytes, &mut chunks)?;
+    Ok(chunks)
+}
+
+fn split_batches(
+    batches: Vec<RecordBatch>,
+    max_bytes: usize,
+    chunks: &mut Vec<String>,
+) -> Result<(), Error> {
+    let num_rows = batches.iter().map(RecordBatch::num_rows).sum::<usize>();
+    if num_rows == 0 {
+        return Ok(());
+    }
+    let encoded = encode_batches(&batches)?;
+    if encoded.len() <= max_bytes || num_rows == 1 {
+        chunks.push(encoded);
+        return Ok(());
+    }
+
+    // Halve the rows until each half fits.
+    let mut remaining = num_rows / 2;
+    let mut head = vec![];
+    let mut tail = vec![];
+    for batch in batches {
+        if remaining == 0 {
+            tail.push(batch);
+        } else if batch.num_rows() <= remaining {
+            remaining -= batch.num_rows();
+            head.push(batch);
+        } else {
+            head.push(batch.slice(0, remaining));
+            tail.push(batch.slice(remaining, batch.num_rows() - remaining));
+            remaining = 0;
+        }
+    }
+    split_batches(head, max_bytes, chunks)?;
+    split_batches(tail, max_bytes, chunks)
+}
+
+fn decode_error(err: ArrowError) -> Error {
+    let error = Error::new(
+        ErrorKind::Unexpected,
+        "failed to read arrow result set".to_string(),
+    );
+    match err {
+        // The stream ended early, e.g., the response was cut off; fetching again may succeed.
+        ArrowError::IoError(..) => error.set_source(err).set_temporary(),
+        err => error.set_source(err).set_permanent(),
+    }
+}
+
+/// Convert record batches into rows of values according to the schema.
+pub(crate) fn batches_to_values(
+    schema: &Schema,
+    batches: &[RecordBatch],
+) -> Result<Vec<Vec<Value>>, Error> {
+    let num_fields = schema.fields().len();
+    let mut rows = Vec::with_capacity(batches.iter().map(RecordBatch::num_rows).sum());
+    for batch in batches {
+        check_num_columns(batch, num_fields)?;
+
+        let columns = schema
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .enumerate()
+            .map(|(i, (field, column))| {
+                column_values(field, column.as_ref()).map_err(|err| err.with_context("column", i))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let offset = rows.len();
+        rows.extend((0..batch.num_rows()).map(|_| Vec::with_capacity(num_fields)));
+        for column in columns {
+            for (row, value) in rows[offset..].iter_mut().zip(column) {
+                row.push(value);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Convert a single column of the record batches into values.
+pub(crate) fn batches_column(
+    schema: &Schema,
+    batches: &[RecordBatch],
+    index: usize,
+) -> Result<Vec<Value>, Error> {
+    let num_fields = schema.fields().len();
+    let mut values = Vec::with_capacity(batches.iter().map(RecordBatch::num_rows).sum());
+    for batch in batches {
+        check_num_columns(batch, num_fields)?;
+        let column = column_values(&schema.fields()[index], batch.column(index).as_ref())
+            .map_err(|err| err.with_context("column", index))?;
+        values.extend(column);
+    }
+    Ok(values)
+}
+
+fn check_num_columns(batch: &RecordBatch, num_fields: usize) -> Result<(), Error> {
+    if batch.num_columns() != num_fields {
+        return Err(Error::new(
+            ErrorKind::Unexpected,
+            format!(
+                "arrow batch has {} columns but the schema declares {num_fields} fields",
+                batch.num_columns()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn column_values(field: &FieldSchema, column: &dyn Array) -> Result<Vec<Value>, Error> {
+    let values = match (field.data_type(), column.data_type()) {
+        (DataType::Int, ArrowDataType::Int64) => {
+            let array = column.as_primitive::<Int64Type>();
+            collect(column, |i| Ok(Value::Int(array.value(i))))
+        }
+        (DataType::UInt, ArrowDataType::UInt64) => {
+            let array = column.as_primitive::<UInt64Type>();
+            collect(column, |i| Ok(Value::UInt(array.value(i))))
+        }
+        (DataType::Float, ArrowDataType::Float64) => {
+            let array = column.as_primitive::<Float64Type>();
+            collect(column, |i| Ok(Value::Float(array.value(i))))
+        }
+        (DataType::Decimal, ArrowDataType::Decimal128(_, scale)) if *scale >= 0 => {
+            let array = column.as_primitive::<Decimal128Type>();
+            let scale = *scale as u32;
+            collect(column, |i| {
+                rust_decimal::Decimal::try_from_i128_with_scale(array.value(i), scale)
+                    .map(Value::Decimal)
+                    .map_err(|err| {
+                        Error::new(
+                            ErrorKind::Unexpected,
+                            format!("failed to convert decimal value: {err}"),
+                        )
+                    })
+            })
+        }
+        (DataType::Boolean, ArrowDataType::Boolean) => {
+            let array = column.as_boolean();
+            collect(column, |i| Ok(Value::Boolean(array.value(i))))
+        }
+        (DataType::Timestamp, ArrowDataType::Timestamp(unit, _)) => {
+            let value = |i| match unit {
+                TimeUnit::Second => jiff::Timestamp::from_second(
+                    column.as_primitive::<TimestampSecondType>().value(i),
+                ),
+                TimeUnit::Millisecond => jiff::Timestamp::from_millisecond(
+                    column.as_primitive::<TimestampMillisecondType>().value(i),
+                ),
+                TimeUnit::Microsecond => jiff::Timestamp::from_microsecond(
+                    column.as_primitive::<TimestampMicrosecondType>().value(i),
+                ),
+                TimeUnit::Nanosecond => jiff::Timestamp::from_nanosecond(i128::from(
+                    column.as_primitive::<TimestampNanosecondType>().value(i),
+                )),
+            };
+            collect(column, |i| {
+                value(i).map(Value::Timestamp).map_err(|err| {
+                    Error::new(
+                        ErrorKind::Unexpected,
+                        format!("failed to convert timestamp value: {err}"),
+                    )
+                })
+            })
+        }
+        (DataType::Interval, ArrowDataType::Interval(IntervalUnit::MonthDayNano)) => {
+            let array = column.as_primitive::<IntervalMonthDayNanoType>();
+            collect(column, |i| {
+                let value = array.value(i);
+                month_day_nano_to_span(value.months, value.days, value.nanoseconds)
+                    .map(Value::Interval)
+            })
+        }
+        (DataType::Interval, ArrowDataType::Duration(unit)) => collect(column, |i| {
+            let value = match unit {
+                TimeUnit::Second => jiff::SignedDuration::from_secs(
+                    column.as_primitive::<DurationSecondType>().value(i),
+                ),
+                TimeUnit::Millisecond => jiff::SignedDuration::from_millis(
+                    column.as_primitive::<DurationMillisecondType>().value(i),
+                ),
+                TimeUnit::Microsecond => jiff::SignedDuration::from_micros(
+                    column.as_primitive::<DurationMicrosecondType>().value(i),
+                ),
+                TimeUnit::Nanosecond => jiff::SignedDuration::from_nanos(
+                    column.as_primitive::<DurationNanosecondType>().value(i),
+                ),
+            };
+            jiff::Span::try_from(value)
+                .map(Value::Interval)
+                .map_err(|err| {
+                    Error::new(
+                        ErrorKind::Unexpected,
+                        format!("failed to convert interval value: {err}"),
+                    )
+                })
+        }),
+        (DataType::Binary, ArrowDataType::Binary) => {
+            let array = column.as_binary::<i32>();
+            collect(column, |i| Ok(Value::Binary(hex::encode(array.value(i)))))
+        }
+        (DataType::Binary, ArrowDataType::LargeBinary) => {
+            let array = column.as_binary::<i64>();
+            collect(column, |i| Ok(Value::Binary(hex::encode(array.value(i)))))
+        }
+        // Any other combination goes through the string form of the cell, as if it had been
+        // returned in JSON; this covers string, array, object and any values.
+        _ => {
+            let options = FormatOptions::default();
+            let formatter = ArrayFormatter::try_new(column, &options).map_err(|err| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    format!("unsupported arrow type {}", column.data_type()),
+                )
+                .set_source(err)
+            })?;
+            collect(column, |i| {
+                parse_cell(field.data_type(), formatter.value(i).to_string())
+                    .map_err(|err| Error::new(ErrorKind::Unexpected, err.message))
+            })
+        }
+    }?;
+    Ok(values)
+}
+
+/// Build a record batch from rows of values according to the schema.
+///
+/// Timestamps map to UTC microsecond timestamps and intervals to month-day-nano intervals. Binary
+/// values are decoded into bytes, while arrays, objects and any values are kept as JSON strings.
+pub(crate) fn values_to_batch(schema: &Schema, rows: &[Vec<Value>]) -> Result<RecordBatch, Error> {
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    let mut columns = Vec::with_capacity(schema.fields().len());
+    for (i, field) in schema.fields().iter().enumerate() {
+        let column = value_column(field, i, rows).map_err(|err| err.with_context("column", i))?;
+        fields.push(Field::new(field.name(), column.data_type().clone(), true));
+        columns.push(column);
+    }
+
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    RecordBatch::try_new_with_options(Arc::new(ArrowSchema::new(fields)), columns, &options)
+        .map_err(|err| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "failed to build arrow record batch".to_string(),
+            )
+            .set_source(err)
+        })
+}
+
+fn value_column(field: &FieldSchema, index: usize, rows: &[Vec<Value>]) -> Result<ArrayRef, Error> {
+    let column: ArrayRef = match field.data_type() {
+        DataType::Int => Arc::new(Int64Array::from(column_cells(
+            rows,
+            index,
+            |value| match value {
+                Value::Int(v) => Some(*v),
+                _ => None,
+            },
+        )?)),
+        DataType::UInt => Arc::new(UInt64Array::from(column_cells(
+            rows,
+            index,
+            |value| match value {
+                Value::UInt(v) => Some(*v),
+                _ => None,
+            },
+        )?)),
+        DataType::Float => Arc::new(Float64Array::from(column_cells(
+            rows,
+            index,
+            |value| match value {
+                Value::Float(v) => Some(*v),
+                _ => None,
+            },
+        )?)),
+        DataType::Decimal => {
+            let cells = column_cells(rows, index, |value| match value {
+                Value::Decimal(v) => Some(*v),
+                _ => None,
+            })?;
+            let (mantissas, precision, scale) = decimal_mantissas(field, cells)?;
+            Arc::new(
+                Decimal128Array::from(mantissas)
+                    .with_precision_and_scale(precision as u8, scale as i8)
+                    .map_err(|err| {
+                        Error::new(
+                            ErrorKind::Unexpected,
+                            "failed to build decimal column".to_string(),
+                        )
+                        .set_source(err)
+                    })?,
+            )
+        }
+        DataType::Boolean => {
+            Arc::new(BooleanArray::from(column_cells(
+                rows,
+                index,
+                |value| match value {
+                    Value::Boolean(v) => Some(*v),
+                    _ => None,
+                },
+            )?))
+        }
+        DataType::Timestamp => Arc::new(
+            TimestampMicrosecondArray::from(column_cells(rows, index, |value| match value {
+                Value::Timestamp(v) => Some(v.as_microsecond()),
+                _ => None,
+            })?)
+            .with_timezone("UTC"),
+        ),
+        DataType::Interval => Arc::new(IntervalMonthDayNanoArray::from(column_cells(
+            rows,
+            index,
+            |value| match value {
+                Value::Interval(v) => span_to_month_day_nano(v),
+                _ => None,
+            },
+        )?)),
+        DataType::Binary => Arc::new(BinaryArray::from_iter(column_cells(
+            rows,
+            index,
+            |value| match value {
+                Value::Binary(v) => decode_binary(v).ok(),
+                _ => None,
+            },
+        )?)),
+        DataType::String | DataType::Array | DataType::Object | DataType::Any => Arc::new(
+            StringArray::from(column_cells(rows, index, |value| match value {
+                Value::String(v) | Value::Array(v) | Value::Object(v) | Value::Any(v) => {
+                    Some(v.clone())
+                }
+                _ => None,
+            })?),
+        ),
+        DataType::Null => {
+            column_cells(rows, index, |_| None::<()>)?;
+            Arc::new(NullArray::new(rows.len()))
+        }
+    };
+    Ok(column)
+}
+
+/// Convert the components of an Arrow month-day-nano interval into a span.
+fn month_day_nano_to_span(months: i32, days: i32, nanos: i64) -> Result<jiff::Span, Error> {
+    let error = |message: String| Error::new(ErrorKind::Unexpected, message);
+    // A span has a single sign for all its units.
+    let signs = [months.signum(), days.signum(), nanos.signum() as i32];
+    if signs.contains(&1) && signs.contains(&-1) {
+        return Err(error(format!(
+            "cannot convert interval with mixed signs: {months} months, {days} days, {nanos} nanoseconds"
+        )));
+    }
+
+    let span = jiff::Span::try_from(jiff::SignedDuration::from_nanos(nanos.abs()))
+        .and_then(|span| span.try_months(months.abs()))
+        .and_then(|span| span.try_days(days.abs()))
+        .map_err(|err| error(format!("failed to convert interval value: {err}")))?;
+    Ok(if signs.contains(&-1) {
+        span.negate()
+    } else {
+        span
+    })
+}
+
+/// Convert a span into an Arrow month-day-nano interval, or `None` if it is out of range.
+fn span_to_month_day_nano(span: &jiff::Span) -> Option<IntervalMonthDayNano> {
+    let months = i64::from(span.get_years()) * 12 + i64::from(span.get_months());
+    let days = i64::from(span.get_weeks()) * 7 + i64::from(span.get_days());
+    let time = span.years(0).months(0).weeks(0).days(0);
+    let nanos = jiff::SignedDuration::try_from(time).ok()?.as_nanos();
+    Some(IntervalMonthDayNano::new(
+        i32::try_from(months).ok()?,
+        i32::try_from(days).ok()?,
+        i64::try_from(nanos).ok()?,
+    ))
+}
+
+fn collect(
+    column: &dyn Array,
+    value: impl Fn(usize) -> Result<Value, Error>,
+) -> Result<Vec<Value>, Error> {
+    (0..column.len())
+        .map(|i| {
+            if column.is_null(i) {
+                Ok(Value::Null)
+            } else {
+                value(i)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::ArrayRef;
+    use arrow_array::BooleanArray;
+    use arrow_array::Int64Array;
+    use arrow_array::StringArray;
+    use arrow_array::TimestampMicrosecondArray;
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_partial_json;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use super::*;
+    use crate::Client;
+    use crate::ResultSet;
+    use crate::protocol::StatementResultSet;
+
+    fn encode(batch: &RecordBatch) -> Vec<u8> {
+        let mut writer = StreamWriter::try_new(vec![], &batch.schema()).unwrap();
+        writer.write(batch).unwrap();
+        writer.into_inner().unwrap()
+    }
+
+    fn test_batch() -> RecordBatch {
+        RecordBatch::try_from_iter([
+            (
+                "id",
+                Arc::new(Int64Array::from(vec![Some(1), None])) as ArrayRef,
+            ),
+            (
+                "name",
+                Arc::new(StringArray::from(vec![Some("alpha"), Some("beta")])) as ArrayRef,
+            ),
+            (
+                "active",
+                Arc::new(BooleanArray::from(vec![Some(true), None])) as ArrayRef,
+            ),
+            (
+                "ts",
+                Arc::new(TimestampMicrosecondArray::from(vec![
+                    Some(1_700_000_000_000_000),
+                    None,
+                ])) as ArrayRef,
+            ),
+            (
+                "tags",
+                Arc::new(StringArray::from(vec![Some(r#"["a"]"#), None])) as ArrayRef,
+            ),
+        ])
+        .unwrap()
+    }
+
+    fn test_result_set(payload: &[u8]) -> ResultSet {
+        let result_set: StatementResultSet = serde_json::from_value(json!({
+            "metadata": {
+                "fields": [
+                    {"name": "id", "data_type": "int"},
+                    {"name": "name", "data_type": "string"},
+                    {"name": "active", "data_type": "boolean"},
+                    {"name": "ts", "data_type": "timestamp"},
+                    {"name": "tags", "data_type": "array"},
+                ],
+                "num_rows": 2,
+            },
+            "format": "arrow",
+            "rows": STANDARD.encode(payload),
+        }))
+        .unwrap();
+        ResultSet::from_statement_result_set(result_set)
+    }
+
+    #[test]
+    fn test_arrow_into_values() {
+        let result_set = test_result_set(&encode(&test_batch()));
+        assert!(result_set.json_rows().is_none());
+        let values = result_set.into_values().unwrap();
+        assert_eq!(
+            format!("{values:?}"),
+            r#"[[1, 'alpha', true, 2023-11-14T22:13:20Z, ["a"]], [NULL, 'beta', NULL, NULL, NULL]]"#
+        );
+    }
+
+    #[test]
+    fn test_json_into_record_batches() {
+        let result_set: StatementResultSet = serde_json::from_value(json!({
+            "metadata": {
+                "fields": [
+                    {"name": "id", "data_type": "int"},
+                    {"name": "ts", "data_type": "timestamp"},
+                    {"name": "payload", "data_type": "binary"},
+                    {"name": "tags", "data_type": "array"},
+                    {"name": "span", "data_type": "interval"},
+                    {"name": "amount", "data_type": "decimal"},
+                ],
+                "num_rows": 2,
+            },
+            "format": "json",
+            "rows": [
+                ["1", "2023-11-14T22:13:20Z", "beef", r#"["a"]"#, "P1Y2M3DT4S", "1.5"],
+                [null, null, null, null, null, "-0.25"],
+            ],
+        }))
+        .unwrap();
+        let result_set = ResultSet::from_statement_result_set(result_set);
+        let schema = result_set.schema().clone();
+        let batches = result_set.into_record_batches().unwrap();
+        assert_eq!(batches.len(), 1);
+
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.column(0).data_type(), &ArrowDataType::Int64);
+        assert_eq!(
+            batch.column(1).data_type(),
+            &ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        );
+        assert_eq!(batch.column(2).as_binary::<i32>().value(0), [0xbe, 0xef]);
+        assert_eq!(batch.column(3).as_string::<i32>().value(0), r#"["a"]"#);
+        assert_eq!(
+            batch
+                .column(4)
+                .as_primitive::<IntervalMonthDayNanoType>()
+                .value(0),
+            IntervalMonthDayNano::new(14, 3, 4_000_000_000)
+        );
+
+        let amounts = batch.column(5).as_primitive::<Decimal128Type>();
+        assert_eq!(amounts.scale(), 2);
+        assert_eq!(amounts.values(), &[150, -25]);
+
+        let values = batches_to_values(&schema, &batches).unwrap();
+        assert!(matches!(&values[0][2], Value::Binary(v) if v == "beef"));
+        assert_eq!(format!("{:?}", values[0][4]), "14mo3d4s");
+        assert_eq!(
+            format!("{:?}", values[1]),
+            "[NULL, NULL, NULL, NULL, NULL, -0.25]"
+        );
+    }
+
+    #[test]
+    fn test_arrow_truncated_stream_is_temporary() {
+        let payload = encode(&test_batch());
+        let err = test_result_set(&payload[..payload.len() - 16])
+            .into_values()
+            .unwrap_err();
+        assert!(err.is_temporary(), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_ingest_arrow() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(body_partial_json(json!({
+                "type": "committed",
+                "data": {"format": "arrow"},
+                "statement": "INSERT INTO t",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": 6})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let batch = test_batch();
+        let result = client
+            .ingest_arrow(&[batch.clone(), batch.clone()], "INSERT INTO t")
+            .await
+            .unwrap();
+        assert_eq!(result.num_rows_inserted, 6);
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = requests[0].body_json().unwrap();
+        let batches = decode_batches(body["data"]["rows"].as_str().unwrap()).unwrap();
+        assert_eq!(batches, [batch.clone(), batch]);
+    }
+
+    #[test]
+    fn test_encode_batches_requires_batches() {
+        assert!(encode_batches(&[]).is_err());
+    }
+
+    #[test]
+    fn test_split_encoded_batches() {
+        let batch = RecordBatch::try_from_iter([(
+            "k",
+            Arc::new(Int64Array::from((0..100).collect::<Vec<_>>())) as ArrayRef,
+        )])
+        .unwrap();
+        let rows = encode_batches(&[batch.clone()]).unwrap();
+        assert_eq!(
+            split_encoded_batches(&rows, rows.len()).unwrap(),
+            [rows.clone()]
+        );
+
+        let max_bytes = rows.len() * 2 / 3;
+        let chunks = split_encoded_batches(&rows, max_bytes).unwrap();
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= max_bytes));
+        let keys = chunks
+            .iter()
+            .flat_map(|chunk| decode_batches(chunk).unwrap())
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(keys, (0..100).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::ResultSet;
+use crate::lexer;
+use crate::lexer::TokenKind;
+
+/// An in-memory LRU cache of statement results, e.g., for dashboards that issue the same query
+/// every few seconds.
+///
+/// Install the cache with [`ClientBuilder::result_cache`] and opt statements into it with
+/// [`Statement::with_cache`]. Results are keyed on the statement text, with runs of whitespace
+/// collapsed and a trailing semicolon stripped, along with the bound parameters and result
+/// options. An entry expires `ttl` after it was cached, and the least recently used entry is
+/// evicted when the cache is full.
+///
+/// Clones of the cache share its entries, so the cache can be kept around to invalidate entries
+/// after writes.
+///
+/// [`ClientBuilder::result_cache`]: crate::ClientBuilder::result_cache
+/// [`Statement::with_cache`]: crate::Statement::with_cache
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: Arc<Mutex<Entries>>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    map: HashMap<CacheKey, Entry>,
+    /// Bumped on every access to order the entries by recency.
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    result_set: ResultSet,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    statement: String,
+    params: String,
+}
+
+impl CacheKey {
+    pub(crate) fn new(statement: &str, params: String) -> Self {
+        Self {
+            statement: normalize(statement),
+            params,
+        }
+    }
+}
+
+impl ResultCache {
+    /// Create a cache of at most `capacity` results, each kept for at most `ttl`.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            entries: Arc::new(Mutex::new(Entries::default())),
+        }
+    }
+
+    /// Drop the cached results of the statement, for all parameter values.
+    pub fn invalidate(&self, statement: &str) {
+        let statement = normalize(statement);
+        let mut entries = self.lock();
+        entries.map.retain(|key, _| key.statement != statement);
+    }
+
+    /// Drop all cached results.
+    pub fn invalidate_all(&self) {
+        self.lock().map.clear();
+    }
+
+    /// The number of cached results, including expired ones not yet evicted.
+    pub fn len(&self) -> usize {
+        self.lock().map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, key: &CacheKey) -> Option<ResultSet> {
+        let mut entries = self.lock();
+        entries.tick += 1;
+        let tick = entries.tick;
+        let entry = entries.map.get_mut(key)?;
+        if entry.expires_at <= Instant::now() {
+            entries.map.remove(key);
+            return None;
+        }
+        entry.last_used = tick;
+        Some(entry.result_set.clone())
+    }
+
+    pub(crate) fn insert(&self, key: CacheKey, result_set: ResultSet) {
+        let mut entries = self.lock();
+        entries.tick += 1;
+        let tick = entries.tick;
+        let now = Instant::now();
+        if !entries.map.contains_key(&key) && entries.map.len() >= self.capacity {
+            entries.map.retain(|_, entry| entry.expires_at > now);
+            if entries.map.len() >= self.capacity {
+                let lru = entries
+                    .map
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone());
+                if let Some(lru) = lru {
+                    entries.map.remove(&lru);
+                }
+            }
+        }
+        entries.map.insert(
+            key,
+            Entry {
+                result_set,
+                expires_at: now + self.ttl,
+                last_used: tick,
+            },
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Collapse runs of whitespace outside quotes and comments into a single space, and strip
+/// surrounding whitespace and a trailing semicolon.
+fn normalize(statement: &str) -> String {
+    let statement = statement.trim().trim_end_matches(';').trim_end();
+    let mut normalized = String::with_capacity(statement.len());
+    let mut after_line_comment = false;
+    for token in lexer::tokenize(statement) {
+        match token.kind {
+            // Keep a line break ending a comment, which separates it from the next line.
+            TokenKind::Whitespace if after_line_comment => normalized.push('\n'),
+            TokenKind::Whitespace => normalized.push(' '),
+            _ => normalized.push_str(token.text),
+        }
+        after_line_comment = token.kind == TokenKind::LineComment;
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::testing;
+
+    fn result_set() -> ResultSet {
+        let result_set =
+            serde_json::from_value(testing::result_set(&[("v", "int")], json!([["1"]]))).unwrap();
+        ResultSet::from_statement_result_set(result_set)
+    }
+
+    fn key(statement: &str) -> CacheKey {
+        CacheKey::new(statement, String::new())
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let cache = ResultCache::new(2, Duration::from_secs(60));
+        cache.insert(key("FROM a"), result_set());
+        cache.insert(key("FROM b"), result_set());
+        assert!(cache.get(&key("FROM  a;")).is_some());
+        cache.insert(key("FROM c"), result_set());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key("FROM a")).is_some());
+        assert!(cache.get(&key("FROM b")).is_none());
+
+        cache.invalidate("FROM\na");
+        assert!(cache.get(&key("FROM a")).is_none());
+        cache.invalidate_all();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_expire() {
+        let cache = ResultCache::new(2, Duration::ZERO);
+        cache.insert(key("FROM a"), result_set());
+        assert!(cache.get(&key("FROM a")).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize("  FROM t\n\t WHERE  a = 'x  y' ;\n"),
+            "FROM t WHERE a = 'x  y'"
+        );
+        assert_eq!(
+            normalize("FROM t -- a  b\n  LIMIT 1"),
+            "FROM t -- a  b\nLIMIT 1"
+        );
+        assert_ne!(normalize("-- x\nFROM t"), normalize("-- x FROM t"));
+    }
+}
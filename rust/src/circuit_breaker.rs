@@ -0,0 +1,205 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::BoxFuture;
+use crate::Error;
+use crate::ErrorKind;
+use crate::Middleware;
+use crate::Next;
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    /// Requests flow through; counts the consecutive failures so far.
+    Closed(usize),
+    /// Requests are rejected until the given instant.
+    Open(Instant),
+    /// A trial request has been in flight since the given instant.
+    HalfOpen(Instant),
+}
+
+/// A [`Middleware`] that stops sending requests to a server that keeps failing.
+///
+/// After `failure_threshold` consecutive failures, the circuit opens and every request fails
+/// immediately with [`ErrorKind::CircuitOpen`] for the `cooldown` duration. Then a single trial
+/// request is let through: if it succeeds the circuit closes, otherwise it opens again.
+///
+/// Transport errors and `5xx` responses count as failures. Health checks bypass the breaker: they
+/// are never rejected nor counted, so they can probe a server while the circuit is open.
+///
+/// The breaker keeps its state per middleware instance, which is shared by all clones of the
+/// [`Client`] it is installed on.
+///
+/// [`Client`]: crate::Client
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: usize,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: usize, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Mutex::new(State::Closed(0)),
+        }
+    }
+
+    fn acquire(&self) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let now = Instant::now();
+        match *state {
+            State::Closed(_) => Ok(()),
+            // A trial that never reports back, e.g. because its future was dropped, must not
+            // keep the circuit half-open forever.
+            State::Open(until) | State::HalfOpen(until) if now >= until => {
+                *state = State::HalfOpen(now + self.cooldown);
+                Ok(())
+            }
+            State::Open(until) | State::HalfOpen(until) => Err(Error::new(
+                ErrorKind::CircuitOpen,
+                "circuit breaker is open after consecutive failures".to_string(),
+            )
+            .with_context("retry_after", format!("{:?}", until - now))
+            .set_temporary()),
+        }
+    }
+
+    fn record(&self, success: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        *state = match (*state, success) {
+            (_, true) => State::Closed(0),
+            (State::Closed(failures), false) if failures + 1 < self.failure_threshold => {
+                State::Closed(failures + 1)
+            }
+            (_, false) => State::Open(Instant::now() + self.cooldown),
+        };
+    }
+}
+
+impl Middleware for CircuitBreaker {
+    fn handle<'a>(
+        &'a self,
+        request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response, Error>> {
+        Box::pin(async move {
+            if is_health_check(&request) {
+                return next.run(request).await;
+            }
+            self.acquire()?;
+            let result = next.run(request).await;
+            let success = match &result {
+                Ok(response) => !response.status().is_server_error(),
+                Err(_) => false,
+            };
+            self.record(success);
+            result
+        })
+    }
+}
+
+fn is_health_check(request: &reqwest::Request) -> bool {
+    request.method() == reqwest::Method::GET && request.url().path().ends_with("/v1/health")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use super::*;
+    use crate::Client;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let server = MockServer::start().await;
+        let unhealthy = Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount_as_scoped(&server)
+            .await;
+
+        let cooldown = Duration::from_millis(100);
+        let client = Client::builder(server.uri())
+            .with_middleware(CircuitBreaker::new(2, cooldown))
+            .build()
+            .unwrap();
+        for _ in 0..2 {
+            let err = client.execute("SELECT 1").await.unwrap_err();
+            assert_ne!(err.kind(), ErrorKind::CircuitOpen);
+        }
+        let err = client.execute("SELECT 1").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::CircuitOpen);
+        assert!(err.is_temporary());
+        drop(unhealthy);
+
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .expect(2)
+            .mount(&server)
+            .await;
+        tokio::time::sleep(cooldown).await;
+        client.execute("SELECT 1").await.unwrap();
+        client.execute("SELECT 1").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_health() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(3)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .with_middleware(CircuitBreaker::new(1, Duration::from_secs(60)))
+            .build()
+            .unwrap();
+        // Failed health checks do not open the circuit.
+        for _ in 0..2 {
+            let err = client.health().await.unwrap_err();
+            assert_ne!(err.kind(), ErrorKind::CircuitOpen);
+        }
+        let err = client.execute("SELECT 1").await.unwrap_err();
+        assert_ne!(err.kind(), ErrorKind::CircuitOpen);
+        let err = client.execute("SELECT 1").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::CircuitOpen);
+        // Nor does an open circuit reject them.
+        let err = client.health().await.unwrap_err();
+        assert_ne!(err.kind(), ErrorKind::CircuitOpen);
+    }
+}
@@ -12,83 +12,658 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "record")]
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
 use fastrace_reqwest::traceparent_headers;
+use jiff::SignedDuration;
+use mea::semaphore::OwnedSemaphorePermit;
+use mea::semaphore::Semaphore;
 use reqwest::IntoUrl;
+use reqwest::RequestBuilder;
 use reqwest::Url;
+use reqwest::header::CONTENT_ENCODING;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::LOCATION;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::BufReader;
 use uuid::Uuid;
 
+use crate::Compression;
+use crate::CsvOptions;
 use crate::Error;
 use crate::ErrorKind;
+use crate::IngestBuilder;
 use crate::IngestStreamBuilder;
+use crate::IngestType;
+use crate::Middleware;
+use crate::PollPolicy;
+use crate::PreparedStatement;
+use crate::QueryPlan;
+use crate::ResultCache;
+use crate::ResultFormat;
+use crate::ResultSet;
+use crate::RetryPolicy;
+use crate::RowStream;
+use crate::Schema;
 use crate::Statement;
+use crate::StatementCheckpoint;
+use crate::StatementExecutor;
 use crate::Table;
+use crate::TimeoutOrigin;
+use crate::Writer;
+use crate::lexer;
+use crate::lexer::Token;
+use crate::lexer::TokenKind;
+use crate::middleware::Middlewares;
+use crate::middleware::Next;
+use crate::protocol::ErrorStatus;
+use crate::protocol::HealthStatus;
 use crate::protocol::IngestData;
 use crate::protocol::IngestRequest;
 use crate::protocol::IngestResult;
-use crate::protocol::IngestType;
 use crate::protocol::Response;
-use crate::protocol::ResultFormat;
+use crate::protocol::StatementCancelRequest;
 use crate::protocol::StatementCancelResult;
+use crate::protocol::StatementEntry;
+use crate::protocol::StatementFilter;
+use crate::protocol::StatementList;
 use crate::protocol::StatementRequest;
 use crate::protocol::StatementRequestParams;
 use crate::protocol::StatementStatus;
+use crate::protocol::json_len;
+use crate::retry::IDEMPOTENCY_KEY;
+use crate::script::split_statements;
 use crate::statement::StatementHandle;
 
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+const DEFAULT_NDJSON_BATCH_BYTES: usize = 16 * 1024 * 1024;
+#[cfg(feature = "parquet")]
+const PARQUET_BATCH_ROWS: usize = 8192;
+
+/// How the client handles redirect responses returned by the server.
+///
+/// This only takes effect for redirects that reach the SDK. A `reqwest::Client` passed to
+/// [`Client::new`] follows redirects on its own unless it is configured with
+/// `reqwest::redirect::Policy::none()`; clients created by [`ClientBuilder`] always are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectPolicy {
+    /// Follow up to the given number of redirects.
+    ///
+    /// The redirected request keeps the method, headers and body of the original request, so
+    /// that authentication and trace headers survive a redirect to another node.
+    Follow(usize),
+    /// Return an [`ErrorKind::Redirected`] error carrying the redirect location in its `location`
+    /// context, for the caller to handle.
+    Error,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy::Follow(DEFAULT_MAX_REDIRECTS)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Client {
     endpoint: Url,
     client: reqwest::Client,
+    redirect: RedirectPolicy,
+    middlewares: Middlewares,
+    connections: Option<Arc<Semaphore>>,
+    in_flight: Option<Arc<Semaphore>>,
+    max_ingest_bytes: Option<usize>,
+    ingest_compression: Compression,
+    poll_policy: PollPolicy,
+    exec_timeout: Option<SignedDuration>,
+    result_cache: Option<ResultCache>,
+    #[cfg(feature = "record")]
+    recorder: Option<Arc<crate::record::Recorder>>,
+    #[cfg(feature = "record")]
+    replayer: Option<Arc<crate::record::Replayer>>,
 }
 
 impl Client {
     pub fn new<E: IntoUrl>(endpoint: E, client: reqwest::Client) -> Result<Self, Error> {
-        match endpoint.into_url() {
-            Ok(endpoint) => Ok(Self { endpoint, client }),
-            Err(err) => Err(Error::new(
-                ErrorKind::ConfigInvalid,
-                "failed to parse endpoint".to_string(),
-            )
-            .set_source(err)),
-        }
+        Ok(Self {
+            endpoint: parse_endpoint(endpoint.into_url())?,
+            client,
+            redirect: RedirectPolicy::default(),
+            middlewares: Middlewares::default(),
+            connections: None,
+            in_flight: None,
+            max_ingest_bytes: None,
+            ingest_compression: Compression::default(),
+            poll_policy: PollPolicy::default(),
+            exec_timeout: None,
+            result_cache: None,
+            #[cfg(feature = "record")]
+            recorder: None,
+            #[cfg(feature = "record")]
+            replayer: None,
+        })
+    }
+
+    pub fn builder<E: IntoUrl>(endpoint: E) -> ClientBuilder {
+        ClientBuilder::new(endpoint)
     }
 
     pub fn statement(&self, statement: String) -> Statement {
         Statement::new(self.clone(), statement)
     }
 
+    /// Run the statement to completion and return its result.
+    ///
+    /// Use [`statement`](Self::statement) to set options or to manage the statement through its
+    /// [`StatementHandle`].
+    pub async fn execute(&self, statement: impl Into<String>) -> Result<ResultSet, Error> {
+        self.statement(statement.into()).execute().await
+    }
+
+    /// Run the statements of the script one after another, in order, and return the result of
+    /// each.
+    ///
+    /// The script is split at the semicolons outside quotes and comments. Running stops at the
+    /// first statement that fails, and the returned list ends with its error, which carries the
+    /// index of the statement in its `statement_index` context. The statements run independently,
+    /// so those before a failed one are not rolled back.
+    pub async fn execute_script(&self, script: &str) -> Vec<Result<ResultSet, Error>> {
+        let mut results = vec![];
+        for (i, statement) in split_statements(script).into_iter().enumerate() {
+            let result = self
+                .execute(statement)
+                .await
+                .map_err(|err| err.with_context("statement_index", i));
+            let failed = result.is_err();
+            results.push(result);
+            if failed {
+                break;
+            }
+        }
+        results
+    }
+
+    /// Prepare a statement to execute repeatedly with different parameter values.
+    pub fn prepare(&self, statement: String) -> PreparedStatement {
+        PreparedStatement::new(self.clone(), statement)
+    }
+
+    /// The result cache installed with [`ClientBuilder::result_cache`], e.g., to invalidate
+    /// cached results after a write.
+    pub fn result_cache(&self) -> Option<&ResultCache> {
+        self.result_cache.as_ref()
+    }
+
+    /// Attach to a statement submitted earlier, possibly by another process.
+    ///
+    /// The handle fetches results in JSON and polls with the policy of the client; see
+    /// [`StatementHandle::with_result_format`] and [`StatementHandle::with_poll_policy`] to fetch
+    /// as the statement was originally submitted.
     pub fn statement_handle(&self, statement_id: Uuid) -> StatementHandle {
         StatementHandle::new(self.clone(), statement_id, ResultFormat::Json)
     }
 
+    /// List the statements known to the server that match the filter, e.g., to monitor the
+    /// statements running on a cluster.
+    ///
+    /// The listing endpoint is not part of the confirmed server API yet; servers that do not
+    /// serve it fail the call.
+    pub async fn list_statements(
+        &self,
+        filter: StatementFilter,
+    ) -> Result<Vec<StatementEntry>, Error> {
+        match self.fetch_statements(filter).await? {
+            Response::Success(list) => Ok(list.statements),
+            Response::Failed(err) => Err(map_failed_response(
+                err,
+                "failed to list statements".to_string(),
+            )),
+        }
+    }
+
+    /// Resume a statement from a checkpoint taken with [`StatementHandle::checkpoint`], fetching
+    /// and polling it as the original handle did.
+    ///
+    /// The elapsed time reported by the handle counts from the resumption.
+    pub fn resume_statement(&self, checkpoint: StatementCheckpoint) -> StatementHandle {
+        StatementHandle::resume(self.clone(), checkpoint)
+    }
+
+    /// Submit the statements one after another, in order, and return a handle for each.
+    ///
+    /// A statement that fails on submission, or that the server reports as failed or cancelled
+    /// right away, yields an error in its place.
+    ///
+    /// With `stop_on_error`, each statement runs to completion before the next one is submitted,
+    /// so that dependent steps run in order. A statement that fails, on submission or while
+    /// running, yields an error, and the statements after it are not submitted: the returned
+    /// list ends with that error. Without it, the statements run concurrently on the server, and
+    /// failures after submission show up only on their handles.
+    ///
+    /// Under [`ClientBuilder::max_in_flight`], each statement of the batch holds a slot only while
+    /// it is submitted or awaited, so that a batch larger than the limit does not wait on itself.
+    pub async fn submit_batch(
+        &self,
+        statements: Vec<Statement>,
+        stop_on_error: bool,
+    ) -> Vec<Result<StatementHandle, Error>> {
+        let mut handles = Vec::with_capacity(statements.len());
+        for (i, statement) in statements.into_iter().enumerate() {
+            let handle = async {
+                let mut handle = statement.submit().await?.check_not_failed()?;
+                if !stop_on_error {
+                    return Ok(handle.release_slot());
+                }
+                handle.wait(None).await?;
+                Ok(handle)
+            }
+            .await
+            .map_err(|err: Error| err.with_context("batch_index", i));
+            let failed = handle.is_err();
+            handles.push(handle);
+            if failed && stop_on_error {
+                break;
+            }
+        }
+        handles
+    }
+
+    /// Run the statement and stream its rows.
+    ///
+    /// See [`RowStream`] for the cancellation semantics.
+    pub fn query_iter(&self, statement: impl Into<String>) -> RowStream {
+        RowStream::new(self.statement(statement.into()))
+    }
+
+    /// Run the statement and return `limit` rows of its result, starting from row `offset`.
+    ///
+    /// The page is requested from the server. A server that paginates may report the size of the
+    /// whole result in [`ResultSet::total_rows`]. A server that returns more than `limit` rows is
+    /// taken to have ignored the page and returned the whole result, which is sliced on the
+    /// client; such a server cannot be told apart from a paginating one when the whole result has
+    /// at most `limit` rows, which are then returned as is.
+    pub async fn query_page(
+        &self,
+        statement: impl Into<String>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<ResultSet, Error> {
+        let result_set = self
+            .statement(statement.into())
+            .with_page(offset, limit)
+            .execute()
+            .await?;
+        Ok(result_set.into_page(offset, limit))
+    }
+
+    /// Return the result schema of the statement without fetching its rows.
+    ///
+    /// The statement is run with a `LIMIT 0` clause, so it must be a single query that accepts
+    /// one. A trailing `LIMIT n` clause is replaced, and trailing semicolons and comments are
+    /// dropped.
+    pub async fn columns(&self, statement: impl Into<String>) -> Result<Schema, Error> {
+        let statement = limit_zero(&statement.into())?;
+        let result_set = self.statement(statement).execute().await?;
+        Ok(result_set.schema().clone())
+    }
+
+    /// Return the plan of the statement, parsed from the output of `EXPLAIN`.
+    ///
+    /// See [`QueryPlan::from_result_set`] for how the output is parsed.
+    pub async fn explain(&self, statement: impl Into<String>) -> Result<QueryPlan, Error> {
+        let statement = statement.into();
+        let statement = statement.trim_end().trim_end_matches(';');
+        let result_set = self
+            .statement(format!("EXPLAIN {statement}"))
+            .execute()
+            .await?;
+        QueryPlan::from_result_set(&result_set)
+    }
+
+    /// Create an executor that runs at most `max_concurrency` statements at once.
+    pub fn executor(&self, max_concurrency: usize) -> StatementExecutor {
+        StatementExecutor::new(self.clone(), max_concurrency)
+    }
+
     pub fn table(&self, table: impl Into<String>) -> Table {
         Table::new(self.clone(), table.into())
     }
 
+    /// Build an ingest request into the table, e.g., `db.schema.table`.
+    pub fn ingest_into(&self, table: impl Into<String>) -> IngestBuilder {
+        IngestBuilder::new(self.clone(), table.into())
+    }
+
+    /// Create a [`Writer`] that buffers rows and inserts them through the statement in the
+    /// background, with the default thresholds of [`ingest_stream`](Self::ingest_stream).
+    pub fn writer(&self, statement: impl Into<String>) -> Writer {
+        Writer::from(self.ingest_stream(statement).build())
+    }
+
+    /// Create an [`ArrowSink`](crate::ArrowSink) that inserts each record batch sent into it
+    /// through the transform statement.
+    #[cfg(feature = "arrow")]
+    pub fn arrow_sink(&self, transform: impl Into<String>) -> crate::ArrowSink {
+        crate::ArrowSink::new(self.clone(), transform.into())
+    }
+
     pub fn ingest_stream(&self, statement: impl Into<String>) -> IngestStreamBuilder {
         IngestStreamBuilder::new(self.clone(), statement.into())
     }
 
+    /// Check that the server is live and ready.
     pub async fn health_check(&self) -> Result<(), Error> {
+        let status = self.health().await?;
+        if status.ready {
+            Ok(())
+        } else {
+            Err(
+                Error::new(ErrorKind::Unexpected, "server is not ready".to_string())
+                    .set_temporary(),
+            )
+        }
+    }
+
+    /// Fetch the liveness and readiness of the server.
+    ///
+    /// A plain `OK` body means the server is live and ready. The JSON body with `live` and `ready`
+    /// fields, and reading a `503 Service Unavailable` response as live but not ready, are
+    /// unconfirmed: no server is known to report either yet.
+    pub async fn health(&self) -> Result<HealthStatus, Error> {
         let url = self.make_url("v1/health")?;
-        self.client.get(url).send().await.map_err(|err| {
+        let response = self
+            .send(self.client.get(url), || {
+                "failed to send health check request".to_string()
+            })
+            .await?;
+
+        let code = response.status();
+        let payload = response.bytes().await.map_err(|err| {
             Error::new(
                 ErrorKind::Unexpected,
-                "failed to send health check request".to_string(),
+                "failed to read health check response".to_string(),
             )
             .set_source(err)
         })?;
-        Ok(())
+
+        if code == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            return Ok(HealthStatus {
+                live: true,
+                ready: false,
+            });
+        }
+        if !code.is_success() {
+            // Any unhealthy response may recover, e.g., while a node restarts behind a proxy.
+            let message = String::from_utf8_lossy(&payload).into_owned();
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("health check failed: {}", ErrorStatus::new(code, message)),
+            )
+            .with_context("status", code.as_u16())
+            .set_temporary());
+        }
+
+        // Known servers respond with a plain-text "OK".
+        if payload.trim_ascii() == b"OK" {
+            return Ok(HealthStatus {
+                live: true,
+                ready: true,
+            });
+        }
+        serde_json::from_slice(&payload).map_err(|err| {
+            Error::new(
+                ErrorKind::Unexpected,
+                format!(
+                    "failed to parse health check response: {}",
+                    String::from_utf8_lossy(&payload)
+                ),
+            )
+            .set_source(err)
+        })
+    }
+
+    /// Measure the round-trip latency of a health check request.
+    pub async fn ping(&self) -> Result<Duration, Error> {
+        let start = Instant::now();
+        self.health_check().await?;
+        Ok(start.elapsed())
     }
 
     pub async fn insert(&self, rows: String, transform: String) -> Result<IngestResult, Error> {
-        match self
-            .ingest(IngestRequest {
-                ty: IngestType::Committed,
-                data: IngestData::Json { rows },
-                statement: transform,
+        self.insert_data(IngestData::Json { rows }, transform).await
+    }
+
+    /// Insert the record batches, which must share a schema, through the transform statement.
+    #[cfg(feature = "arrow")]
+    pub async fn ingest_arrow(
+        &self,
+        batches: &[arrow_array::RecordBatch],
+        transform: impl Into<String>,
+    ) -> Result<IngestResult, Error> {
+        let data = IngestData::from_record_batches(batches)?;
+        self.insert_data(data, transform.into()).await
+    }
+
+    /// Insert the rows of a Parquet file through the transform statement, e.g., to backfill a
+    /// table straight from a lakehouse file.
+    ///
+    /// The reader is usually a [`std::fs::File`], or the file's bytes; see
+    /// [`Client::ingest_parquet_file`] to read a file by path. Its row groups are decoded on a
+    /// blocking thread and sent as Arrow ingest data, in batches of up to 8192 rows, each inserted
+    /// on its own. If a batch fails, the batches before it stay inserted; their row count is
+    /// carried in the `num_rows_inserted` context of the error.
+    #[cfg(feature = "parquet")]
+    pub async fn ingest_parquet<R>(
+        &self,
+        reader: R,
+        transform: impl Into<String>,
+    ) -> Result<IngestResult, Error>
+    where
+        R: parquet::file::reader::ChunkReader + 'static,
+    {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let transform = transform.into();
+        let mut num_rows_inserted = 0;
+        let mut batches = read_parquet(num_rows_inserted, move || {
+            Ok(ParquetRecordBatchReaderBuilder::try_new(reader)?
+                .with_batch_size(PARQUET_BATCH_ROWS)
+                .build()?)
+        })
+        .await?;
+        loop {
+            let (rest, batch) = read_parquet(num_rows_inserted, move || {
+                let batch = batches.next().transpose()?;
+                Ok((batches, batch))
             })
-            .await?
-        {
+            .await?;
+            let Some(batch) = batch else {
+                return Ok(IngestResult { num_rows_inserted });
+            };
+            batches = rest;
+            let result = self
+                .ingest_arrow(&[batch], transform.clone())
+                .await
+                .map_err(|err| err.with_context("num_rows_inserted", num_rows_inserted))?;
+            num_rows_inserted += result.num_rows_inserted;
+        }
+    }
+
+    /// Insert the rows of the Parquet file at the path through the transform statement, like
+    /// [`Client::ingest_parquet`].
+    #[cfg(feature = "parquet")]
+    pub async fn ingest_parquet_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        transform: impl Into<String>,
+    ) -> Result<IngestResult, Error> {
+        let path = path.as_ref();
+        let file = read_parquet(0, {
+            let path = path.to_path_buf();
+            move || Ok(std::fs::File::open(path)?)
+        })
+        .await
+        .map_err(|err| err.with_context("path", path.display()))?;
+        self.ingest_parquet(file, transform).await
+    }
+
+    /// Insert newline-delimited JSON objects read from the reader through the transform
+    /// statement, e.g., to ship a log file without loading it fully.
+    ///
+    /// The lines are sent in batches of up to 16 MiB, each inserted on its own, and blank lines
+    /// are skipped. If a batch fails, the batches before it stay inserted; their row count is
+    /// carried in the `num_rows_inserted` context of the error.
+    pub async fn ingest_ndjson<R: AsyncRead + Unpin>(
+        &self,
+        reader: R,
+        transform: impl Into<String>,
+    ) -> Result<IngestResult, Error> {
+        let mut batcher = JsonBatcher::new(self, transform.into());
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if batcher.read_line(&mut reader, &mut line).await? == 0 {
+                return batcher.finish().await;
+            }
+            let row = line.trim();
+            if !row.is_empty() {
+                batcher.push(row).await?;
+            }
+        }
+    }
+
+    /// Insert CSV records read from the reader through the transform statement, each as a JSON
+    /// object of its fields.
+    ///
+    /// The fields are named after the header line, or the columns given in the options, and
+    /// renamed by their mapping; see [`CsvOptions`]. Field values are strings, or nulls, for the
+    /// transform statement to convert. Records are sent in batches like with
+    /// [`ingest_ndjson`](Self::ingest_ndjson), and blank lines are skipped.
+    ///
+    /// Every record must have as many fields as the header line, or as the first record without
+    /// a header. Every mapping must refer to an existing column, and no two columns may be
+    /// ingested as the same field.
+    pub async fn ingest_csv<R: AsyncRead + Unpin>(
+        &self,
+        reader: R,
+        transform: impl Into<String>,
+        options: &CsvOptions,
+    ) -> Result<IngestResult, Error> {
+        let mut batcher = JsonBatcher::new(self, transform.into());
+        let mut reader = BufReader::new(reader);
+        let mut names: Option<Vec<String>> = None;
+        let mut num_records = 0;
+        let mut record = String::new();
+        loop {
+            record.clear();
+            // A quoted field may span lines.
+            let mut eof = false;
+            while !eof && (record.is_empty() || !options.is_complete(&record)) {
+                eof = batcher.read_line(&mut reader, &mut record).await? == 0;
+            }
+            let line = record.trim_end_matches(['\r', '\n']);
+            if !line.trim().is_empty() {
+                let fields = options.parse_record(line);
+                if names.is_none() {
+                    let n = fields.len();
+                    if options.has_header() {
+                        names = Some(options.field_names(Some(fields), n)?);
+                        continue;
+                    }
+                    names = Some(options.field_names(None, n)?);
+                }
+                let names = names.as_deref().expect("field names must be set");
+                if fields.len() != names.len() {
+                    return Err(Error::new(
+                        ErrorKind::Unexpected,
+                        format!(
+                            "CSV record has {} fields, expected {}",
+                            fields.len(),
+                            names.len()
+                        ),
+                    )
+                    .with_context("record", num_records));
+                }
+                num_records += 1;
+                let row = names
+                    .iter()
+                    .zip(fields)
+                    .map(|(name, field)| (name.clone(), serde_json::Value::from(field)))
+                    .collect::<serde_json::Map<_, _>>();
+                batcher
+                    .push(&serde_json::Value::Object(row).to_string())
+                    .await?;
+            }
+            if eof {
+                return batcher.finish().await;
+            }
+        }
+    }
+
+    /// Insert the given data through the transform statement.
+    pub async fn insert_data(
+        &self,
+        data: IngestData,
+        transform: String,
+    ) -> Result<IngestResult, Error> {
+        self.insert_request(IngestRequest {
+            ty: IngestType::Committed,
+            data,
+            statement: transform,
+            idempotency_key: None,
+        })
+        .await
+    }
+}
+
+impl Client {
+    pub(crate) async fn insert_request(
+        &self,
+        request: IngestRequest,
+    ) -> Result<IngestResult, Error> {
+        let chunks = match self.max_ingest_bytes {
+            Some(max_bytes) if json_len(&request) > max_bytes => {
+                // Leave room for the rest of the body.
+                let envelope = IngestRequest {
+                    ty: request.ty,
+                    data: request.data.without_rows(),
+                    statement: request.statement.clone(),
+                    idempotency_key: None,
+                };
+                request
+                    .data
+                    .split(max_bytes.saturating_sub(json_len(&envelope)))?
+            }
+            _ => return self.insert_chunk(request).await,
+        };
+
+        let mut num_rows_inserted = 0;
+        for (i, data) in chunks.into_iter().enumerate() {
+            let chunk = IngestRequest {
+                ty: request.ty,
+                data,
+                statement: request.statement.clone(),
+                idempotency_key: request
+                    .idempotency_key
+                    .as_ref()
+                    .map(|key| format!("{key}-{i}")),
+            };
+            let result = self.insert_chunk(chunk).await.map_err(|err| {
+                err.with_context("num_rows_inserted", num_rows_inserted)
+                    .with_context("chunk_index", i)
+            })?;
+            num_rows_inserted += result.num_rows_inserted;
+        }
+        Ok(IngestResult { num_rows_inserted })
+    }
+
+    async fn insert_chunk(&self, request: IngestRequest) -> Result<IngestResult, Error> {
+        match self.ingest(request).await? {
             Response::Success(result) => Ok(result),
             Response::Failed(err) => Err(map_failed_response(
                 err,
@@ -96,30 +671,24 @@ impl Client {
             )),
         }
     }
-}
 
-impl Client {
     #[fastrace::trace]
     pub(crate) async fn submit_statement(
         &self,
         request: StatementRequest,
     ) -> Result<Response<StatementStatus>, Error> {
         let url = self.make_url("v1/statements")?;
+        let max_result_bytes = request.params.max_result_bytes;
         let response = self
-            .client
-            .post(url)
-            .headers(traceparent_headers())
-            .json(&request)
-            .send()
-            .await
-            .map_err(|err| {
-                Error::new(
-                    ErrorKind::Unexpected,
-                    format!("failed to submit statement: {request:?}"),
-                )
-                .set_source(err)
-            })?;
-        Response::from_http_response(response).await
+            .send(
+                self.client
+                    .post(url)
+                    .headers(traceparent_headers())
+                    .json(&request),
+                || format!("failed to submit statement: {request:?}"),
+            )
+            .await?;
+        Response::from_http_response_with_limit(response, max_result_bytes).await
     }
 
     #[fastrace::trace]
@@ -127,23 +696,41 @@ impl Client {
         &self,
         statement_id: Uuid,
         params: StatementRequestParams,
+        wait: Option<Duration>,
     ) -> Result<Response<StatementStatus>, Error> {
         let path = format!("v1/statements/{statement_id}");
         let url = self.make_url(&path)?;
-        let response = self
+        let mut builder = self
             .client
             .get(url)
             .headers(traceparent_headers())
-            .query(&params)
-            .send()
-            .await
-            .map_err(|err| {
-                Error::new(
-                    ErrorKind::Unexpected,
-                    format!("failed to fetch statement: {statement_id:?}"),
-                )
-                .set_source(err)
-            })?;
+            .query(&params);
+        if let Some(wait) = wait {
+            builder = builder.query(&[("wait_ms", wait.as_millis())]);
+        }
+        let response = self
+            .send(builder, || {
+                format!("failed to fetch statement: {statement_id:?}")
+            })
+            .await?;
+        Response::from_http_response_with_limit(response, params.max_result_bytes).await
+    }
+
+    #[fastrace::trace]
+    pub(crate) async fn fetch_statements(
+        &self,
+        filter: StatementFilter,
+    ) -> Result<Response<StatementList>, Error> {
+        let url = self.make_url("v1/statements")?;
+        let response = self
+            .send(
+                self.client
+                    .get(url)
+                    .headers(traceparent_headers())
+                    .query(&filter),
+                || format!("failed to list statements: {filter:?}"),
+            )
+            .await?;
         Response::from_http_response(response).await
     }
 
@@ -151,22 +738,19 @@ impl Client {
     pub(crate) async fn cancel_statement(
         &self,
         statement_id: Uuid,
+        request: StatementCancelRequest,
     ) -> Result<Response<StatementCancelResult>, Error> {
         let path = format!("v1/statements/{statement_id}/cancel");
         let url = self.make_url(&path)?;
+        let mut builder = self.client.post(url).headers(traceparent_headers());
+        if request.reason.is_some() {
+            builder = builder.json(&request);
+        }
         let response = self
-            .client
-            .post(url)
-            .headers(traceparent_headers())
-            .send()
-            .await
-            .map_err(|err| {
-                Error::new(
-                    ErrorKind::Unexpected,
-                    format!("failed to cancel statement: {statement_id:?}"),
-                )
-                .set_source(err)
-            })?;
+            .send(builder, || {
+                format!("failed to cancel statement: {statement_id:?}")
+            })
+            .await?;
         Response::from_http_response(response).await
     }
 
@@ -177,23 +761,151 @@ impl Client {
     ) -> Result<Response<IngestResult>, Error> {
         let format = request.data.format();
         let url = self.make_url("v1/ingest")?;
-        let response = self
+        let body = serde_json::to_vec(&request).map_err(|err| {
+            Error::new(
+                ErrorKind::Unexpected,
+                format!("failed to serialize ingest request in {format}"),
+            )
+            .set_source(err)
+        })?;
+        let mut builder = self
             .client
             .post(url)
             .headers(traceparent_headers())
-            .json(&request)
-            .send()
-            .await
-            .map_err(|err| {
-                Error::new(
-                    ErrorKind::Unexpected,
-                    format!("failed to ingest data in {format}"),
-                )
-                .set_source(err)
-            })?;
+            .header(CONTENT_TYPE, "application/json")
+            .body(self.ingest_compression.encode(body).await?);
+        if let Some(encoding) = self.ingest_compression.content_encoding() {
+            builder = builder.header(CONTENT_ENCODING, encoding);
+        }
+        if let Some(key) = &request.idempotency_key {
+            builder = builder.header(IDEMPOTENCY_KEY, key);
+        }
+        let _slot = self.acquire_in_flight_slot().await;
+        let response = self
+            .send(builder, || format!("failed to ingest data in {format}"))
+            .await?;
         Response::from_http_response(response).await
     }
 
+    /// Send the request, following redirects according to the configured [`RedirectPolicy`].
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        message: impl Fn() -> String + Send + Sync,
+    ) -> Result<reqwest::Response, Error> {
+        let make_error =
+            |err: anyhow::Error| Error::new(ErrorKind::Unexpected, message()).set_source(err);
+
+        let mut request = request.build().map_err(|err| make_error(err.into()))?;
+        let mut redirects = 0;
+        loop {
+            let url = request.url().clone();
+            let next = request.try_clone();
+            let response = Next::new(self, self.middlewares.as_slice(), &message)
+                .run(request)
+                .await?;
+            if !response.status().is_redirection() {
+                return Ok(response);
+            }
+            let Some(location) = response.headers().get(LOCATION) else {
+                return Ok(response);
+            };
+
+            let location = location
+                .to_str()
+                .map_err(|err| make_error(err.into()))
+                .and_then(|location| url.join(location).map_err(|err| make_error(err.into())))?;
+
+            match self.redirect {
+                RedirectPolicy::Error => {
+                    return Err(Error::new(
+                        ErrorKind::Redirected,
+                        format!("server redirected the request to {location}"),
+                    )
+                    .with_context("location", location));
+                }
+                RedirectPolicy::Follow(max_redirects) => {
+                    if redirects >= max_redirects {
+                        return Err(Error::new(
+                            ErrorKind::Unexpected,
+                            format!("too many redirects (max_redirects={max_redirects})"),
+                        )
+                        .with_context("location", location));
+                    }
+                    let Some(mut next) = next else {
+                        return Err(Error::new(
+                            ErrorKind::Unexpected,
+                            "cannot follow redirect of a streaming request body".to_string(),
+                        )
+                        .with_context("location", location));
+                    };
+                    *next.url_mut() = location;
+                    request = next;
+                    redirects += 1;
+                }
+            }
+        }
+    }
+
+    /// Send a single request over the wire, after all middlewares have run.
+    pub(crate) async fn transport(
+        &self,
+        request: reqwest::Request,
+        message: &(dyn Fn() -> String + Send + Sync),
+    ) -> Result<reqwest::Response, Error> {
+        #[cfg(feature = "record")]
+        if let Some(replayer) = &self.replayer {
+            return replayer.replay(&request).await;
+        }
+        #[cfg(feature = "record")]
+        let captured = self
+            .recorder
+            .as_ref()
+            .map(|_| crate::record::CapturedRequest::new(&request));
+
+        let permit = match &self.connections {
+            Some(connections) => Some(connections.acquire(1).await),
+            None => None,
+        };
+        let response = self.client.execute(request).await.map_err(|err| {
+            let error = if err.is_timeout() {
+                Error::new(ErrorKind::Timeout(TimeoutOrigin::Client), message()).set_temporary()
+            } else if err.is_connect() {
+                // The request never reached the server, so sending it again is safe.
+                Error::new(ErrorKind::Unexpected, message()).set_temporary()
+            } else {
+                Error::new(ErrorKind::Unexpected, message())
+            };
+            error.set_source(err)
+        })?;
+
+        #[cfg(feature = "record")]
+        if let (Some(recorder), Some(captured)) = (&self.recorder, captured) {
+            return recorder.record(captured, response).await;
+        }
+        if permit.is_some() {
+            // Release the connection only once the body is read.
+            return buffer_response(response, message).await;
+        }
+        Ok(response)
+    }
+
+    /// Take a slot under [`ClientBuilder::max_in_flight`], if set, waiting for one to free up.
+    pub(crate) async fn acquire_in_flight_slot(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.in_flight {
+            Some(in_flight) => Some(in_flight.clone().acquire_owned(1).await),
+            None => None,
+        }
+    }
+
+    pub(crate) fn poll_policy(&self) -> PollPolicy {
+        self.poll_policy
+    }
+
+    pub(crate) fn exec_timeout(&self) -> Option<SignedDuration> {
+        self.exec_timeout
+    }
+
     #[track_caller]
     fn make_url(&self, path: &str) -> Result<Url, Error> {
         self.endpoint.join(path).map_err(|err| {
@@ -202,6 +914,342 @@ impl Client {
     }
 }
 
+pub struct ClientBuilder {
+    endpoint: Result<Url, reqwest::Error>,
+    redirect: RedirectPolicy,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    max_connections: Option<usize>,
+    max_in_flight: Option<usize>,
+    max_ingest_bytes: Option<usize>,
+    ingest_compression: Compression,
+    poll_policy: PollPolicy,
+    exec_timeout: Option<SignedDuration>,
+    result_cache: Option<ResultCache>,
+    retry_policy: Option<RetryPolicy>,
+    #[cfg(feature = "record")]
+    record: Option<PathBuf>,
+    #[cfg(feature = "record")]
+    replay: Option<PathBuf>,
+}
+
+impl ClientBuilder {
+    fn new<E: IntoUrl>(endpoint: E) -> Self {
+        Self {
+            endpoint: endpoint.into_url(),
+            redirect: RedirectPolicy::default(),
+            middlewares: vec![],
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            max_connections: None,
+            max_in_flight: None,
+            max_ingest_bytes: None,
+            ingest_compression: Compression::default(),
+            poll_policy: PollPolicy::default(),
+            exec_timeout: None,
+            result_cache: None,
+            retry_policy: None,
+            #[cfg(feature = "record")]
+            record: None,
+            #[cfg(feature = "record")]
+            replay: None,
+        }
+    }
+
+    pub fn redirect(mut self, redirect: RedirectPolicy) -> Self {
+        self.redirect = redirect;
+        self
+    }
+
+    /// Keep at most this many idle connections per host in the connection pool.
+    pub fn pool_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max_idle);
+        self
+    }
+
+    /// Close pooled connections that have been idle for longer than this duration.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Cap the number of requests in flight from this client and its clones, and thus the number
+    /// of connections it opens.
+    ///
+    /// Requests beyond the cap wait for a free slot. A slot is held until the response body is
+    /// read, and each hop of a followed redirect takes its own slot. Since every statement issues
+    /// its requests one at a time, a [`StatementExecutor`] with a `max_concurrency` no greater
+    /// than this cap keeps statements from queueing on connections; middlewares such as
+    /// [`CircuitBreaker`] run before a slot is taken.
+    ///
+    /// [`CircuitBreaker`]: crate::CircuitBreaker
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = Some(max_connections.max(1));
+        self
+    }
+
+    /// Cap the number of statements and ingest requests in flight from this client and its
+    /// clones, so that a burst of tasks does not overload the server.
+    ///
+    /// Submitting beyond the cap waits for a free slot. A statement holds its slot from
+    /// submission until its [`StatementHandle`] sees it terminate or is dropped; a statement
+    /// submitted with [`Statement::submit_detached`] holds it until it terminates. The statements
+    /// of a [`Client::submit_batch`] hold a slot only while they are submitted. An ingest request
+    /// holds its slot until the server responds.
+    ///
+    /// A task that holds as many handles of running statements as the cap must poll or drop them
+    /// before it can submit another statement.
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight.max(1));
+        self
+    }
+
+    /// Split ingest requests whose JSON body exceeds this many bytes into multiple ingest
+    /// requests, so that large batches stay within the server's request size limit.
+    ///
+    /// JSON rows are split between lines and Arrow rows between record batch slices; a single
+    /// row larger than the limit is sent on its own. The chunks are sent one after another, each
+    /// retried on its own under the [`retry_policy`](Self::retry_policy), and an idempotency key
+    /// is suffixed with the chunk index. If a chunk fails, the chunks before it stay inserted;
+    /// the error carries their row count in the `num_rows_inserted` context, and the index of
+    /// the failed chunk in the `chunk_index` context.
+    pub fn max_ingest_bytes(mut self, max_ingest_bytes: usize) -> Self {
+        self.max_ingest_bytes = Some(max_ingest_bytes.max(1));
+        self
+    }
+
+    /// Compress the bodies of ingest requests, see [`Compression`].
+    ///
+    /// The server must accept the chosen `Content-Encoding`. The
+    /// [`max_ingest_bytes`](Self::max_ingest_bytes) limit applies before compression.
+    pub fn ingest_compression(mut self, compression: Compression) -> Self {
+        self.ingest_compression = compression;
+        self
+    }
+
+    /// Set how statements of this client poll for their status while waiting to terminate.
+    pub fn poll_policy(mut self, poll_policy: PollPolicy) -> Self {
+        self.poll_policy = poll_policy;
+        self
+    }
+
+    /// Set the default `exec_timeout` of statements of this client.
+    ///
+    /// A statement can override it with [`Statement::with_exec_timeout`].
+    pub fn exec_timeout(mut self, exec_timeout: SignedDuration) -> Self {
+        self.exec_timeout = Some(exec_timeout);
+        self
+    }
+
+    /// Cache the results of statements that opt in with [`Statement::with_cache`].
+    pub fn result_cache(mut self, result_cache: ResultCache) -> Self {
+        self.result_cache = Some(result_cache);
+        self
+    }
+
+    /// Retry requests that fail temporarily, see [`RetryPolicy`].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Add a middleware to the end of the stack that every request flows through.
+    ///
+    /// See [`Middleware`] for the ordering semantics.
+    pub fn with_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Write every request/response pair to the given directory, for debugging.
+    ///
+    /// Sensitive headers such as `Authorization` are redacted in the captures. The directory is
+    /// created when the first response is recorded.
+    #[cfg(feature = "record")]
+    pub fn record(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.record = Some(dir.into());
+        self
+    }
+
+    /// Serve responses from the captures in the given directory instead of the network.
+    ///
+    /// Requests must be issued in the same order as they were recorded. The captures are read when
+    /// the first request is sent.
+    #[cfg(feature = "record")]
+    pub fn replay(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.replay = Some(dir.into());
+        self
+    }
+
+    pub fn build(mut self) -> Result<Client, Error> {
+        let endpoint = parse_endpoint(self.endpoint)?;
+        if let Some(retry_policy) = self.retry_policy {
+            self.middlewares.insert(0, Arc::new(retry_policy));
+        }
+        let mut builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        let client = builder.build().map_err(|err| {
+            Error::new(
+                ErrorKind::ConfigInvalid,
+                "failed to build HTTP client".to_string(),
+            )
+            .set_source(err)
+        })?;
+
+        Ok(Client {
+            endpoint,
+            client,
+            redirect: self.redirect,
+            middlewares: Middlewares::new(self.middlewares),
+            connections: self
+                .max_connections
+                .map(|max_connections| Arc::new(Semaphore::new(max_connections))),
+            in_flight: self
+                .max_in_flight
+                .map(|max_in_flight| Arc::new(Semaphore::new(max_in_flight))),
+            max_ingest_bytes: self.max_ingest_bytes,
+            ingest_compression: self.ingest_compression,
+            poll_policy: self.poll_policy,
+            exec_timeout: self.exec_timeout,
+            result_cache: self.result_cache,
+            #[cfg(feature = "record")]
+            recorder: self
+                .record
+                .map(|dir| Arc::new(crate::record::Recorder::new(dir))),
+            #[cfg(feature = "record")]
+            replayer: self
+                .replay
+                .map(|dir| Arc::new(crate::record::Replayer::new(dir))),
+        })
+    }
+}
+
+/// Run a blocking Parquet read off the async runtime.
+#[cfg(feature = "parquet")]
+async fn read_parquet<T, F>(num_rows_inserted: i64, read: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    let read_error = |err: anyhow::Error| {
+        Error::new(
+            ErrorKind::Unexpected,
+            "failed to read parquet ingest data".to_string(),
+        )
+        .set_source(err)
+        .with_context("num_rows_inserted", num_rows_inserted)
+    };
+    tokio::task::spawn_blocking(read)
+        .await
+        .map_err(|err| read_error(err.into()))?
+        .map_err(read_error)
+}
+
+/// Collects JSON lines into ingest requests of up to [`DEFAULT_NDJSON_BATCH_BYTES`].
+struct JsonBatcher<'a> {
+    client: &'a Client,
+    transform: String,
+    batch: String,
+    num_rows_inserted: i64,
+}
+
+impl<'a> JsonBatcher<'a> {
+    fn new(client: &'a Client, transform: String) -> Self {
+        Self {
+            client,
+            transform,
+            batch: String::new(),
+            num_rows_inserted: 0,
+        }
+    }
+
+    async fn read_line<R: AsyncRead + Unpin>(
+        &self,
+        reader: &mut BufReader<R>,
+        line: &mut String,
+    ) -> Result<usize, Error> {
+        reader.read_line(line).await.map_err(|err| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "failed to read ingest rows".to_string(),
+            )
+            .set_source(err)
+            .with_context("num_rows_inserted", self.num_rows_inserted)
+        })
+    }
+
+    async fn push(&mut self, row: &str) -> Result<(), Error> {
+        if !self.batch.is_empty() {
+            self.batch.push('\n');
+        }
+        self.batch.push_str(row);
+        if self.batch.len() >= DEFAULT_NDJSON_BATCH_BYTES {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        let rows = std::mem::take(&mut self.batch);
+        let result = self
+            .client
+            .insert(rows, self.transform.clone())
+            .await
+            .map_err(|err| err.with_context("num_rows_inserted", self.num_rows_inserted))?;
+        self.num_rows_inserted += result.num_rows_inserted;
+        Ok(())
+    }
+
+    async fn finish(mut self) -> Result<IngestResult, Error> {
+        self.flush().await?;
+        Ok(IngestResult {
+            num_rows_inserted: self.num_rows_inserted,
+        })
+    }
+}
+
+async fn buffer_response(
+    response: reqwest::Response,
+    message: &(dyn Fn() -> String + Send + Sync),
+) -> Result<reqwest::Response, Error> {
+    let make_error =
+        |err: anyhow::Error| Error::new(ErrorKind::Unexpected, message()).set_source(err);
+
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let body = response
+        .bytes()
+        .await
+        .map_err(|err| make_error(err.into()))?;
+
+    let mut builder = http::Response::builder().status(status).version(version);
+    if let Some(response_headers) = builder.headers_mut() {
+        *response_headers = headers;
+    }
+    let response = builder.body(body).map_err(|err| make_error(err.into()))?;
+    Ok(response.into())
+}
+
+fn parse_endpoint(endpoint: reqwest::Result<Url>) -> Result<Url, Error> {
+    endpoint.map_err(|err| {
+        Error::new(
+            ErrorKind::ConfigInvalid,
+            "failed to parse endpoint".to_string(),
+        )
+        .set_source(err)
+    })
+}
+
 fn map_failed_response(err: crate::protocol::ErrorStatus, message: String) -> Error {
     let error = Error::new(ErrorKind::Unexpected, format!("{message}: {err}"));
     match err.code() {
@@ -213,3 +1261,925 @@ fn map_failed_response(err: crate::protocol::ErrorStatus, message: String) -> Er
         _ => error.set_permanent(),
     }
 }
+
+/// Rewrite a query to return no rows, for [`Client::columns`].
+fn limit_zero(statement: &str) -> Result<String, Error> {
+    let make_error = |message: &str| {
+        Error::new(ErrorKind::Unexpected, message)
+            .with_context("statement", statement)
+            .set_permanent()
+    };
+    let is_semicolon = |token: &Token| token.kind == TokenKind::Punct && token.text == ";";
+
+    let mut tokens = lexer::tokenize(statement)
+        .filter(Token::is_code)
+        .collect::<Vec<_>>();
+    while tokens.last().is_some_and(is_semicolon) {
+        tokens.pop();
+    }
+    if tokens.iter().any(is_semicolon) {
+        return Err(make_error("expected a single statement"));
+    }
+    match tokens.as_slice() {
+        [] => Err(make_error("statement is empty")),
+        [.., limit, count]
+            if limit.kind == TokenKind::Word
+                && limit.text.eq_ignore_ascii_case("LIMIT")
+                && count.text.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            Ok(format!("{}LIMIT 0", &statement[..limit.start]))
+        }
+        [.., last] => Ok(format!("{} LIMIT 0", &statement[..last.end()])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::AUTHORIZATION;
+    use reqwest::header::HeaderMap;
+    use reqwest::header::HeaderValue;
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_partial_json;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+
+    use super::*;
+    use crate::DataType;
+    use crate::StatementState;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_query_page() {
+        let server = MockServer::start().await;
+        let mut page = testing::result_set(&[("v", "int")], json!([["3"], ["4"]]));
+        page["metadata"]["total_rows"] = json!(10);
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(
+                json!({"statement": "FROM t", "offset": 2, "limit": 2}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(page)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"statement": "FROM u"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"], ["2"], ["3"], ["4"], ["5"]])),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let result_set = client.query_page("FROM t", 2, 2).await.unwrap();
+        assert_eq!(result_set.total_rows(), Some(10));
+        let values = result_set.into_values().unwrap();
+        assert_eq!(format!("{values:?}"), "[[3], [4]]");
+
+        // The server ignored the pagination parameters.
+        let result_set = client.query_page("FROM u", 2, 2).await.unwrap();
+        assert_eq!(result_set.total_rows(), Some(5));
+        let values = result_set.into_values().unwrap();
+        assert_eq!(format!("{values:?}"), "[[3], [4]]");
+    }
+
+    #[tokio::test]
+    async fn test_query_page_without_total() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"offset": 2, "limit": 2})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["3"], ["4"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The server honored the page without reporting the total, so it is not sliced again.
+        let client = Client::builder(server.uri()).build().unwrap();
+        let result_set = client.query_page("FROM t", 2, 2).await.unwrap();
+        assert_eq!(result_set.total_rows(), None);
+        let values = result_set.into_values().unwrap();
+        assert_eq!(format!("{values:?}"), "[[3], [4]]");
+    }
+
+    #[tokio::test]
+    async fn test_list_statements() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/statements"))
+            .and(query_param("state", "running"))
+            .and(query_param("limit", "10"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "statements": [{
+                    "statement_id": testing::STATEMENT_ID,
+                    "status": "running",
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "progress": testing::progress(),
+                }],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let statements = client
+            .list_statements(
+                StatementFilter::new()
+                    .with_state(StatementState::Running)
+                    .with_limit(10),
+            )
+            .await
+            .unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(
+            statements[0].statement_id.to_string(),
+            testing::STATEMENT_ID
+        );
+        assert_eq!(statements[0].status, StatementState::Running);
+    }
+
+    #[tokio::test]
+    async fn test_statement_handle_options() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("format", "json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let result_set = client
+            .statement_handle(testing::STATEMENT_ID.parse().unwrap())
+            .with_result_format(ResultFormat::Json)
+            .with_poll_policy(PollPolicy::fixed(Duration::from_millis(10)))
+            .fetch_into()
+            .await
+            .unwrap();
+        assert_eq!(result_set.column::<i64>("v").unwrap(), [1]);
+    }
+
+    #[tokio::test]
+    async fn test_resume_statement() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("format", "json"))
+            .and(query_param("max_rows", "10"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let poll_policy = PollPolicy::fixed(Duration::from_millis(10));
+        let handle = client
+            .statement("FROM t".to_string())
+            .with_result_format(ResultFormat::Json)
+            .with_max_rows(10)
+            .with_poll_policy(poll_policy)
+            .submit()
+            .await
+            .unwrap();
+        let persisted = serde_json::to_string(&handle.checkpoint()).unwrap();
+        drop(handle);
+
+        let checkpoint: StatementCheckpoint = serde_json::from_str(&persisted).unwrap();
+        assert_eq!(checkpoint.statement_id().to_string(), testing::STATEMENT_ID);
+        let handle = client.resume_statement(checkpoint);
+        assert_eq!(handle.checkpoint().poll_policy(), poll_policy);
+        let result_set = handle.fetch_into().await.unwrap();
+        assert_eq!(result_set.column::<i64>("v").unwrap(), [1]);
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"statement": "SELECT 1"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let result_set = client.execute("SELECT 1").await.unwrap();
+        assert_eq!(result_set.column::<i64>("v").unwrap(), [1]);
+    }
+
+    #[tokio::test]
+    async fn test_default_exec_timeout() {
+        let server = MockServer::start().await;
+        for (statement, exec_timeout) in [("SELECT 1", "PT10S"), ("SELECT 2", "PT1S")] {
+            Mock::given(method("POST"))
+                .and(path("/v1/statements"))
+                .and(body_partial_json(
+                    json!({"statement": statement, "exec_timeout": exec_timeout}),
+                ))
+                .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = Client::builder(server.uri())
+            .exec_timeout(SignedDuration::from_secs(10))
+            .build()
+            .unwrap();
+        client
+            .statement("SELECT 1".to_string())
+            .submit()
+            .await
+            .unwrap();
+        client
+            .statement("SELECT 2".to_string())
+            .with_exec_timeout(SignedDuration::from_secs(1))
+            .submit()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_explain() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(
+                json!({"statement": "EXPLAIN FROM t LIMIT 1"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(
+                    &[("plan", "string")],
+                    json!([["Limit: 1 (rows=1)"], ["  TableScan: t (rows=100)"]]),
+                ),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let plan = client.explain("FROM t LIMIT 1;").await.unwrap();
+        assert_eq!(plan.text, "Limit: 1 (rows=1)\n  TableScan: t (rows=100)");
+        assert_eq!(plan.roots[0].operator, "Limit");
+        assert_eq!(plan.roots[0].children[0].estimated_rows, Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_execute_script() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(
+                json!({"statement": "INSERT INTO t VALUES ('a;b')"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("rows", "int")], json!([["1"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"statement": "FROM u"})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(testing::failed("table u not found")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"statement": "FROM t"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let results = client
+            .execute_script("INSERT INTO t VALUES ('a;b');\nFROM u;\nFROM t;\n")
+            .await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().column::<i64>(0).unwrap(), [1]);
+        let err = results[1].as_ref().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::StatementFailed);
+        assert_eq!(err.context("statement_index"), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch() {
+        let server = MockServer::start().await;
+        for statement in ["INSERT a", "INSERT c"] {
+            Mock::given(method("POST"))
+                .and(path("/v1/statements"))
+                .and(body_partial_json(json!({"statement": statement})))
+                .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"statement": "INSERT b"})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(testing::failed("table b not found")),
+            )
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([])),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let statements = || {
+            ["INSERT a", "INSERT b", "INSERT c"]
+                .into_iter()
+                .map(|statement| client.statement(statement.to_string()))
+                .collect()
+        };
+
+        let handles = client.submit_batch(statements(), false).await;
+        assert_eq!(handles.len(), 3);
+        assert!(handles[0].is_ok());
+        let err = handles[1].as_ref().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::StatementFailed);
+        assert_eq!(err.context("batch_index"), Some("1"));
+        assert!(handles[2].is_ok());
+
+        let handles = client.submit_batch(statements(), true).await;
+        assert_eq!(handles.len(), 2);
+        // The first statement ran to completion before the second was submitted.
+        assert!(handles[0].as_ref().unwrap().is_terminated());
+        assert!(handles[1].is_err());
+
+        // A batch larger than the limit of statements in flight does not wait on itself.
+        let client = Client::builder(server.uri())
+            .max_in_flight(1)
+            .build()
+            .unwrap();
+        let statements = ["INSERT a", "INSERT c", "INSERT a"]
+            .into_iter()
+            .map(|statement| client.statement(statement.to_string()))
+            .collect();
+        let handles = tokio::time::timeout(
+            Duration::from_millis(100),
+            client.submit_batch(statements, false),
+        )
+        .await
+        .unwrap();
+        assert!(handles.iter().all(Result::is_ok));
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_stop_on_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(testing::failed("table a not found")),
+            )
+            .mount(&server)
+            .await;
+
+        // A statement that fails while running stops the batch.
+        let client = Client::builder(server.uri()).build().unwrap();
+        let statements = ["INSERT a", "INSERT b"]
+            .into_iter()
+            .map(|statement| client.statement(statement.to_string()))
+            .collect();
+        let handles = client.submit_batch(statements, true).await;
+        assert_eq!(handles.len(), 1);
+        let err = handles[0].as_ref().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::StatementFailed);
+        assert_eq!(err.context("batch_index"), Some("0"));
+    }
+
+    #[tokio::test]
+    async fn test_columns() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({
+                "statement": "FROM t SELECT a, b LIMIT 0",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("a", "int"), ("b", "string")], json!([])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let schema = client.columns("FROM t SELECT a, b;\n").await.unwrap();
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|field| (field.name(), field.data_type()))
+            .collect::<Vec<_>>();
+        assert_eq!(fields, [("a", DataType::Int), ("b", DataType::String)]);
+    }
+
+    #[test]
+    fn test_limit_zero() {
+        assert_eq!(
+            limit_zero("FROM t -- all rows\n;; /* done */").unwrap(),
+            "FROM t LIMIT 0"
+        );
+        assert_eq!(limit_zero("FROM t\nlimit 10;").unwrap(), "FROM t\nLIMIT 0");
+        assert_eq!(
+            limit_zero("FROM t WHERE s = 'LIMIT 10'").unwrap(),
+            "FROM t WHERE s = 'LIMIT 10' LIMIT 0"
+        );
+        let err = limit_zero("FROM a; FROM b").unwrap_err();
+        assert!(err.to_string().contains("single statement"), "{err}");
+        let err = limit_zero("; -- nothing").unwrap_err();
+        assert!(err.to_string().contains("empty"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_max_connections() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("OK")
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .max_connections(1)
+            .pool_max_idle_per_host(1)
+            .build()
+            .unwrap();
+        let start = Instant::now();
+        let (a, b, c) = tokio::join!(client.health(), client.health(), client.health());
+        a.unwrap();
+        b.unwrap();
+        c.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .max_in_flight(1)
+            .build()
+            .unwrap();
+        let mut handle = client
+            .statement("SELECT 1".to_string())
+            .submit()
+            .await
+            .unwrap();
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(100),
+            client.statement("SELECT 2".to_string()).submit(),
+        )
+        .await;
+        assert!(blocked.is_err());
+
+        // The slot frees up once the handle sees the statement finish, even if it is kept.
+        handle.fetch().await.unwrap();
+        let handle = tokio::time::timeout(
+            Duration::from_millis(100),
+            client.statement("SELECT 3".to_string()).submit(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        drop(handle);
+        client.execute("SELECT 4").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ingest_ndjson() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(body_partial_json(json!({
+                "data": {"format": "json", "rows": "{\"k\":1}\n{\"k\":2}"},
+                "statement": "INSERT INTO t",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": 2})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let reader = "{\"k\":1}\r\n\n  {\"k\":2}".as_bytes();
+        let result = client.ingest_ndjson(reader, "INSERT INTO t").await.unwrap();
+        assert_eq!(result.num_rows_inserted, 2);
+
+        let result = client.ingest_ndjson(&b"\n"[..], "INSERT INTO t").await;
+        assert_eq!(result.unwrap().num_rows_inserted, 0);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_csv() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(body_partial_json(json!({
+                "data": {
+                    "format": "json",
+                    "rows": "{\"id\":\"1\",\"note\":\"a\\nb\"}\n{\"id\":\"2\",\"note\":null}",
+                },
+                "statement": "INSERT INTO t",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": 2})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let reader = "key;comment\r\n1;\"a\nb\"\r\n\r\n2;".as_bytes();
+        let options = CsvOptions::new()
+            .with_delimiter(b';')
+            .with_mapping("key", "id")
+            .with_mapping("comment", "note");
+        let result = client
+            .ingest_csv(reader, "INSERT INTO t", &options)
+            .await
+            .unwrap();
+        assert_eq!(result.num_rows_inserted, 2);
+
+        // A record with a missing field fails before it is sent.
+        let reader = "id,note\n1,a\n2\n".as_bytes();
+        let err = client
+            .ingest_csv(reader, "INSERT INTO t", &CsvOptions::new())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("CSV record has 1 fields, expected 2")
+        );
+        assert_eq!(err.context("record"), Some("1"));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[tokio::test]
+    async fn test_ingest_parquet() {
+        use std::sync::Arc;
+
+        use arrow_array::Int64Array;
+        use arrow_array::RecordBatch;
+        use parquet::arrow::ArrowWriter;
+        use parquet::file::properties::WriterProperties;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(body_partial_json(json!({
+                "data": {"format": "arrow"},
+                "statement": "INSERT INTO t",
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": 5000})),
+            )
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        // A single row group, sent in two batches.
+        let batch = RecordBatch::try_from_iter([(
+            "k",
+            Arc::new(Int64Array::from_iter_values(0..10_000)) as _,
+        )])
+        .unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(10_000)
+            .build();
+        let mut file = vec![];
+        let mut writer = ArrowWriter::try_new(&mut file, batch.schema(), Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let result = client
+            .ingest_parquet(bytes::Bytes::from(file.clone()), "INSERT INTO t")
+            .await
+            .unwrap();
+        assert_eq!(result.num_rows_inserted, 10_000);
+
+        let path = std::env::temp_dir().join(format!("scopedb-{}.parquet", uuid::Uuid::now_v7()));
+        std::fs::write(&path, file).unwrap();
+        let result = client.ingest_parquet_file(&path, "INSERT INTO t").await;
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap().num_rows_inserted, 10_000);
+
+        let err = client
+            .ingest_parquet_file(&path, "INSERT INTO t")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.context("path"),
+            Some(path.display().to_string().as_str())
+        );
+
+        let err = client
+            .ingest_parquet(bytes::Bytes::from_static(b"not parquet"), "INSERT INTO t")
+            .await
+            .unwrap_err();
+        assert_eq!(err.context("num_rows_inserted"), Some("0"));
+    }
+
+    #[tokio::test]
+    async fn test_max_ingest_bytes() {
+        let server = MockServer::start().await;
+        let row = |k: i64| json!({"k": k});
+        // The first chunk is sent by both ingests below.
+        let chunks = [
+            (format!("{}\n{}", row(1), row(2)), 2, 2),
+            (row(3).to_string(), 1, 1),
+        ];
+        // Room for the first two rows, whose quotes and line break are escaped in the body.
+        let envelope = json_len(&IngestRequest {
+            ty: IngestType::Committed,
+            data: IngestData::Json {
+                rows: String::new(),
+            },
+            statement: "SELECT $0 INSERT INTO t".to_string(),
+            idempotency_key: None,
+        });
+        let max_bytes = envelope + json_len(&chunks[0].0) - 2;
+        for (i, (rows, num_rows, hits)) in chunks.into_iter().enumerate() {
+            Mock::given(method("POST"))
+                .and(path("/v1/ingest"))
+                .and(body_partial_json(json!({
+                    "data": {"format": "json", "rows": rows},
+                })))
+                .and(header(IDEMPOTENCY_KEY, format!("key-{i}")))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({"num_rows_inserted": num_rows})),
+                )
+                .expect(hits)
+                .mount(&server)
+                .await;
+        }
+
+        let client = Client::builder(server.uri())
+            .max_ingest_bytes(max_bytes)
+            .build()
+            .unwrap();
+        let rows = [row(1), row(2), row(3)];
+        let result = client
+            .ingest_into("t")
+            .data(IngestData::from_json_rows(&rows).unwrap())
+            .transform("SELECT $0")
+            .idempotency_key("key")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(result.num_rows_inserted, 3);
+
+        let rows = [row(1), row(2), row(4)];
+        let err = client
+            .ingest_into("t")
+            .data(IngestData::from_json_rows(&rows).unwrap())
+            .transform("SELECT $0")
+            .idempotency_key("key")
+            .send()
+            .await
+            .unwrap_err();
+        assert_eq!(err.context("num_rows_inserted"), Some("2"));
+        assert_eq!(err.context("chunk_index"), Some("1"));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn test_ingest_compression() {
+        use std::io::Read;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(header("content-encoding", "gzip"))
+            .and(header("content-type", "application/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": 1})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .ingest_compression(Compression::gzip())
+            .build()
+            .unwrap();
+        let result = client
+            .insert("{\"k\":1}".to_string(), "INSERT INTO t".to_string())
+            .await
+            .unwrap();
+        assert_eq!(result.num_rows_inserted, 1);
+
+        let requests = server.received_requests().await.unwrap();
+        let mut body = String::new();
+        flate2::read::GzDecoder::new(requests[0].body.as_slice())
+            .read_to_string(&mut body)
+            .unwrap();
+        let body = serde_json::from_str::<serde_json::Value>(&body).unwrap();
+        assert_eq!(body["data"]["rows"], "{\"k\":1}");
+    }
+
+    #[tokio::test]
+    async fn test_health() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"live": true, "ready": false})),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("degraded"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("boom"))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let ready = HealthStatus {
+            live: true,
+            ready: true,
+        };
+        let not_ready = HealthStatus {
+            live: true,
+            ready: false,
+        };
+        assert_eq!(client.health().await.unwrap(), ready);
+        assert_eq!(client.health().await.unwrap(), not_ready);
+        assert_eq!(client.health().await.unwrap(), not_ready);
+        let err = client.health().await.unwrap_err();
+        assert!(err.to_string().contains("degraded"), "{err}");
+        let err = client.health().await.unwrap_err();
+        assert!(err.to_string().contains("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_rejects_unhealthy_status() {
+        for code in [404, 500, 503] {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/v1/health"))
+                .respond_with(ResponseTemplate::new(code))
+                .mount(&server)
+                .await;
+
+            let client = Client::builder(server.uri()).build().unwrap();
+            let err = client.health_check().await.unwrap_err();
+            assert!(err.is_temporary(), "{code}: {err}");
+            if code != 503 {
+                assert_eq!(err.context("status"), Some(code.to_string().as_str()));
+            }
+        }
+    }
+
+    fn redirect_to(server: &MockServer, path: &str) -> ResponseTemplate {
+        ResponseTemplate::new(307).insert_header("location", format!("{}{path}", server.uri()))
+    }
+
+    #[tokio::test]
+    async fn test_follow_redirect_preserves_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(redirect_to(&server, "/leader/v1/statements"))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/leader/v1/statements"))
+            .and(header("authorization", "Bearer token"))
+            .and(body_partial_json(json!({"statement": "SELECT 1"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("1", "int")], json!([["1"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer token"));
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap();
+        let client = Client::new(server.uri(), http).unwrap();
+
+        let handle = client
+            .statement("SELECT 1".to_string())
+            .submit()
+            .await
+            .unwrap();
+        assert!(handle.status().unwrap().is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_redirect_policy_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(redirect_to(&server, "/leader/v1/health"))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .redirect(RedirectPolicy::Error)
+            .build()
+            .unwrap();
+        let err = client.health_check().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Redirected);
+        assert_eq!(
+            err.context("location"),
+            Some(format!("{}/leader/v1/health", server.uri()).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redirect_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(redirect_to(&server, "/v1/health"))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .redirect(RedirectPolicy::Follow(2))
+            .build()
+            .unwrap();
+        let err = client.health_check().await.unwrap_err();
+        assert!(err.to_string().contains("too many redirects"));
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use crate::Error;
+use crate::ErrorKind;
+use crate::ResultSet;
+use crate::Value;
+
+/// When to quote the fields of a CSV record.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Quote only fields that contain the delimiter, the quote character or a line break, or
+    /// that start or end with whitespace.
+    #[default]
+    Necessary,
+    /// Quote every field, including the header and nulls.
+    Always,
+    /// Never quote fields, which may produce CSV that cannot be read back.
+    Never,
+}
+
+/// Options for [`ResultSet::write_csv`] and [`Client::ingest_csv`].
+///
+/// By default, a header line is written, fields are separated by commas and quoted with `"`
+/// only when necessary, and nulls are written as empty fields. When reading, the same options
+/// describe the input, and [`with_columns`](Self::with_columns) and
+/// [`with_mapping`](Self::with_mapping) name the fields the records are ingested as.
+///
+/// [`Client::ingest_csv`]: crate::Client::ingest_csv
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    delimiter: u8,
+    quote: u8,
+    quote_style: QuoteStyle,
+    header: bool,
+    null: String,
+    terminator: &'static str,
+    columns: Vec<String>,
+    mapping: BTreeMap<String, String>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            quote_style: QuoteStyle::Necessary,
+            header: true,
+            null: String::new(),
+            terminator: "\n",
+            columns: vec![],
+            mapping: BTreeMap::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the byte that separates fields, `,` by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the delimiter is not ASCII.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        assert!(delimiter.is_ascii(), "CSV delimiter must be ASCII");
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the byte that quotes fields, `"` by default. Quotes inside a field are doubled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the quote is not ASCII.
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        assert!(quote.is_ascii(), "CSV quote must be ASCII");
+        self.quote = quote;
+        self
+    }
+
+    /// Set when to quote fields, [`QuoteStyle::Necessary`] by default.
+    pub fn with_quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Whether to write the field names as the first line, `true` by default.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Set the text written for null values, an empty field by default.
+    pub fn with_null(mut self, null: impl Into<String>) -> Self {
+        self.null = null.into();
+        self
+    }
+
+    /// End lines with `\r\n` as in RFC 4180, rather than `\n`.
+    pub fn with_crlf(mut self, crlf: bool) -> Self {
+        self.terminator = if crlf { "\r\n" } else { "\n" };
+        self
+    }
+
+    /// Name the columns of the input when reading, in order, instead of taking the names from the
+    /// header line. Unnamed columns are named `column_1`, `column_2`, and so on.
+    pub fn with_columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Ingest the column named `column` in the input as the field `field`. Columns without a
+    /// mapping keep their names.
+    pub fn with_mapping(mut self, column: impl Into<String>, field: impl Into<String>) -> Self {
+        self.mapping.insert(column.into(), field.into());
+        self
+    }
+
+    /// The field names to ingest the `n` columns as, given the header line if there is one.
+    ///
+    /// Fails if a mapping refers to a column that does not exist, or if two columns would be
+    /// ingested as the same field.
+    pub(crate) fn field_names(
+        &self,
+        header: Option<Vec<Option<String>>>,
+        n: usize,
+    ) -> Result<Vec<String>, Error> {
+        let header = header.unwrap_or_default();
+        let columns = (0..n)
+            .map(|i| {
+                self.columns
+                    .get(i)
+                    .cloned()
+                    .or_else(|| header.get(i).cloned().flatten())
+                    .unwrap_or_else(|| format!("column_{}", i + 1))
+            })
+            .collect::<Vec<_>>();
+        if let Some(column) = self.mapping.keys().find(|column| !columns.contains(column)) {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("CSV mapping refers to missing column {column:?}"),
+            ));
+        }
+
+        let mut names = Vec::with_capacity(n);
+        for column in columns {
+            let name = self.mapping.get(&column).cloned().unwrap_or(column);
+            if names.contains(&name) {
+                return Err(Error::new(
+                    ErrorKind::Unexpected,
+                    format!("duplicate CSV field name {name:?}"),
+                ));
+            }
+            names.push(name);
+        }
+        Ok(names)
+    }
+
+    pub(crate) fn has_header(&self) -> bool {
+        self.header
+    }
+
+    /// Whether the text holds a whole record, i.e., does not end within a quoted field.
+    pub(crate) fn is_complete(&self, record: &str) -> bool {
+        record.bytes().filter(|b| *b == self.quote).count() % 2 == 0
+    }
+
+    /// Split a record, without its line terminator, into its fields; unquoted fields equal to
+    /// the null text are nulls.
+    pub(crate) fn parse_record(&self, record: &str) -> Vec<Option<String>> {
+        let quote = char::from(self.quote);
+        let delimiter = char::from(self.delimiter);
+        let mut fields = vec![];
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        let mut chars = record.chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                if c != quote {
+                    field.push(c);
+                } else if chars.peek() == Some(&quote) {
+                    field.push(quote);
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else if c == quote {
+                in_quotes = true;
+                quoted = true;
+            } else if c == delimiter {
+                fields.push(self.finish_field(std::mem::take(&mut field), quoted));
+                quoted = false;
+            } else {
+                field.push(c);
+            }
+        }
+        fields.push(self.finish_field(field, quoted));
+        fields
+    }
+
+    fn finish_field(&self, field: String, quoted: bool) -> Option<String> {
+        (quoted || field != self.null).then_some(field)
+    }
+
+    fn write_field(&self, out: &mut String, field: &str) {
+        let quote = char::from(self.quote);
+        let quoted = match self.quote_style {
+            QuoteStyle::Always => true,
+            QuoteStyle::Never => false,
+            QuoteStyle::Necessary => {
+                field
+                    .bytes()
+                    .any(|b| b == self.delimiter || b == self.quote || b == b'\n' || b == b'\r')
+                    || field.starts_with(char::is_whitespace)
+                    || field.ends_with(char::is_whitespace)
+            }
+        };
+        if quoted {
+            out.push(quote);
+            for c in field.chars() {
+                if c == quote {
+                    out.push(quote);
+                }
+                out.push(c);
+            }
+            out.push(quote);
+        } else {
+            out.push_str(field);
+        }
+    }
+
+    fn write_record<'a>(&self, out: &mut String, fields: impl IntoIterator<Item = &'a str>) {
+        out.clear();
+        for (i, field) in fields.into_iter().enumerate() {
+            if i > 0 {
+                out.push(char::from(self.delimiter));
+            }
+            self.write_field(out, field);
+        }
+        out.push_str(self.terminator);
+    }
+}
+
+impl ResultSet {
+    /// Write the result set to `writer` as CSV.
+    ///
+    /// Values are written in their [`Display`](std::fmt::Display) form, e.g., timestamps in RFC
+    /// 3339 and binary values in hex, while arrays and objects are written as JSON. Rows are
+    /// converted and written one at a time; wrap unbuffered writers such as files in a
+    /// [`BufWriter`](std::io::BufWriter).
+    pub fn write_csv(&self, mut writer: impl Write, options: &CsvOptions) -> Result<(), Error> {
+        let mut record = String::new();
+        if options.header {
+            let names = self.schema().fields().iter().map(|field| field.name());
+            options.write_record(&mut record, names);
+            write_all(&mut writer, &record)?;
+        }
+
+        let mut cells = vec![];
+        for (i, row) in self.rows().enumerate() {
+            let row = row.map_err(|err| err.with_context("row", i))?;
+            cells.clear();
+            cells.extend(row.iter().map(|value| match value {
+                Value::Null => options.null.clone(),
+                value => value.to_string(),
+            }));
+            options.write_record(&mut record, cells.iter().map(String::as_str));
+            write_all(&mut writer, &record)?;
+        }
+        writer.flush().map_err(io_error)
+    }
+}
+
+fn write_all(writer: &mut impl Write, record: &str) -> Result<(), Error> {
+    writer.write_all(record.as_bytes()).map_err(io_error)
+}
+
+fn io_error(err: std::io::Error) -> Error {
+    Error::new(ErrorKind::Unexpected, "failed to write CSV").set_source(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::StatementResultSet;
+
+    fn test_result_set() -> ResultSet {
+        let result_set: StatementResultSet = serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "fields": [
+                    {"name": "id", "data_type": "int"},
+                    {"name": "name", "data_type": "string"},
+                    {"name": "tags", "data_type": "array"},
+                ],
+                "num_rows": 3,
+            },
+            "format": "json",
+            "rows": [
+                ["1", "alpha", r#"["a","b"]"#],
+                ["2", "say \"hi\"", null],
+                ["3", " padded", "[]"],
+            ],
+        }))
+        .unwrap();
+        ResultSet::from_statement_result_set(result_set)
+    }
+
+    fn write(options: &CsvOptions) -> String {
+        let mut out = vec![];
+        test_result_set().write_csv(&mut out, options).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_write_csv() {
+        assert_eq!(
+            write(&CsvOptions::default()),
+            concat!(
+                "id,name,tags\n",
+                "1,alpha,\"[\"\"a\"\",\"\"b\"\"]\"\n",
+                "2,\"say \"\"hi\"\"\",\n",
+                "3,\" padded\",[]\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_csv_options() {
+        let options = CsvOptions::new()
+            .with_delimiter(b'\t')
+            .with_quote(b'\'')
+            .with_header(false)
+            .with_null("NULL")
+            .with_crlf(true);
+        assert_eq!(
+            write(&options),
+            "1\talpha\t[\"a\",\"b\"]\r\n2\tsay \"hi\"\tNULL\r\n3\t' padded'\t[]\r\n"
+        );
+
+        let options = CsvOptions::new().with_quote_style(QuoteStyle::Always);
+        assert!(write(&options).starts_with("\"id\",\"name\",\"tags\"\n\"1\",\"alpha\""));
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let options = CsvOptions::new();
+        assert!(!options.is_complete("1,\"a\nb"));
+        assert_eq!(
+            options.parse_record(r#"1,"say ""hi""",,"",x"#),
+            [
+                Some("1".to_string()),
+                Some("say \"hi\"".to_string()),
+                None,
+                Some(String::new()),
+                Some("x".to_string()),
+            ]
+        );
+
+        let options = CsvOptions::new()
+            .with_columns(["id"])
+            .with_mapping("name", "display_name");
+        let header = options.parse_record("key,name,extra");
+        assert_eq!(
+            options.field_names(Some(header), 3).unwrap(),
+            ["id", "display_name", "extra"]
+        );
+        assert!(
+            options
+                .field_names(None, 2)
+                .unwrap_err()
+                .to_string()
+                .contains("CSV mapping refers to missing column \"name\"")
+        );
+
+        let options = CsvOptions::new().with_mapping("a", "b");
+        let header = options.parse_record("a,b");
+        assert!(
+            options
+                .field_names(Some(header), 2)
+                .unwrap_err()
+                .to_string()
+                .contains("duplicate CSV field name \"b\"")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "CSV quote must be ASCII")]
+    fn test_non_ascii_quote() {
+        let _ = CsvOptions::new().with_quote(0xe9);
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion of result sets into Polars data frames.
+
+use polars::prelude::Column;
+use polars::prelude::DataFrame;
+use polars::prelude::DataType as PolarsDataType;
+use polars::prelude::Int128Chunked;
+use polars::prelude::IntoSeries;
+use polars::prelude::NamedFrom;
+use polars::prelude::NewChunkedArray;
+use polars::prelude::PolarsError;
+use polars::prelude::Series;
+use polars::prelude::TimeUnit;
+
+use crate::DataType;
+use crate::Error;
+use crate::ErrorKind;
+use crate::FieldSchema;
+use crate::Schema;
+use crate::Value;
+use crate::result::column_cells;
+use crate::result::decimal_mantissas;
+use crate::result::decode_binary;
+use crate::result::interval_to_duration;
+
+/// Build a data frame from rows of values according to the schema.
+///
+/// Timestamps map to microsecond datetimes in UTC and intervals to microsecond durations, with days
+/// taken as 24 hours. Binary values are decoded into bytes, while arrays, objects and any values
+/// are kept as JSON strings.
+pub(crate) fn values_to_dataframe(
+    schema: &Schema,
+    rows: &[Vec<Value>],
+) -> Result<DataFrame, Error> {
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            value_series(field, i, rows)
+                .map(Column::from)
+                .map_err(|err| err.with_context("column", i))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    DataFrame::new(columns).map_err(polars_error)
+}
+
+fn value_series(field: &FieldSchema, index: usize, rows: &[Vec<Value>]) -> Result<Series, Error> {
+    let name = field.name().into();
+    let series = match field.data_type() {
+        DataType::Int => Series::new(
+            name,
+            column_cells(rows, index, |value| match value {
+                Value::Int(v) => Some(*v),
+                _ => None,
+            })?,
+        ),
+        DataType::UInt => Series::new(
+            name,
+            column_cells(rows, index, |value| match value {
+                Value::UInt(v) => Some(*v),
+                _ => None,
+            })?,
+        ),
+        DataType::Float => Series::new(
+            name,
+            column_cells(rows, index, |value| match value {
+                Value::Float(v) => Some(*v),
+                _ => None,
+            })?,
+        ),
+        DataType::Decimal => {
+            let cells = column_cells(rows, index, |value| match value {
+                Value::Decimal(v) => Some(*v),
+                _ => None,
+            })?;
+            let (mantissas, precision, scale) = decimal_mantissas(field, cells)?;
+            Int128Chunked::from_iter_options(name, mantissas.into_iter())
+                .into_decimal(Some(precision as usize), scale as usize)
+                .map_err(polars_error)?
+                .into_series()
+        }
+        DataType::Boolean => Series::new(
+            name,
+            column_cells(rows, index, |value| match value {
+                Value::Boolean(v) => Some(*v),
+                _ => None,
+            })?,
+        ),
+        DataType::Timestamp => Series::new(
+            name,
+            column_cells(rows, index, |value| match value {
+                Value::Timestamp(v) => Some(v.as_microsecond()),
+                _ => None,
+            })?,
+        )
+        .cast(&PolarsDataType::Datetime(TimeUnit::Microseconds, None))
+        .map_err(polars_error)?,
+        DataType::Interval => Series::new(
+            name,
+            column_cells(rows, index, |value| match value {
+                Value::Interval(v) => interval_to_duration(v)
+                    .ok()
+                    .and_then(|v| i64::try_from(v.as_micros()).ok()),
+                _ => None,
+            })?,
+        )
+        .cast(&PolarsDataType::Duration(TimeUnit::Microseconds))
+        .map_err(polars_error)?,
+        DataType::Binary => {
+            let cells = column_cells(rows, index, |value| match value {
+                Value::Binary(v) => decode_binary(v).ok(),
+                _ => None,
+            })?;
+            let cells = cells.iter().map(Option::as_deref).collect::<Vec<_>>();
+            Series::new(name, cells)
+        }
+        DataType::String | DataType::Array | DataType::Object | DataType::Any => {
+            let cells = column_cells(rows, index, |value| match value {
+                Value::String(v) | Value::Array(v) | Value::Object(v) | Value::Any(v) => {
+                    Some(v.as_str())
+                }
+                _ => None,
+            })?;
+            Series::new(name, cells)
+        }
+        DataType::Null => {
+            column_cells(rows, index, |_| None::<()>)?;
+            Series::full_null(name, rows.len(), &PolarsDataType::Null)
+        }
+    };
+    Ok(series)
+}
+
+fn polars_error(err: PolarsError) -> Error {
+    Error::new(
+        ErrorKind::Unexpected,
+        "failed to build polars data frame".to_string(),
+    )
+    .set_source(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::ResultSet;
+    use crate::protocol::StatementResultSet;
+
+    #[test]
+    fn test_into_dataframe() {
+        let result_set: StatementResultSet = serde_json::from_value(json!({
+            "metadata": {
+                "fields": [
+                    {"name": "id", "data_type": "int"},
+                    {"name": "name", "data_type": "string"},
+                    {"name": "ts", "data_type": "timestamp"},
+                    {"name": "payload", "data_type": "binary"},
+                ],
+                "num_rows": 2,
+            },
+            "format": "json",
+            "rows": [
+                ["1", "alpha", "2023-11-14T22:13:20Z", "beef"],
+                ["2", null, null, null],
+            ],
+        }))
+        .unwrap();
+        let df = ResultSet::from_statement_result_set(result_set)
+            .into_dataframe()
+            .unwrap();
+
+        assert_eq!(df.shape(), (2, 4));
+        assert_eq!(
+            df.dtypes(),
+            [
+                PolarsDataType::Int64,
+                PolarsDataType::String,
+                PolarsDataType::Datetime(TimeUnit::Microseconds, None),
+                PolarsDataType::Binary,
+            ]
+        );
+        let ids = df.column("id").unwrap().i64().unwrap();
+        assert_eq!(ids.get(1), Some(2));
+        let names = df.column("name").unwrap().str().unwrap();
+        assert_eq!(names.get(0), Some("alpha"));
+        assert_eq!(names.get(1), None);
+        let payloads = df.column("payload").unwrap().binary().unwrap();
+        assert_eq!(payloads.get(0), Some(&[0xbe, 0xef][..]));
+    }
+}
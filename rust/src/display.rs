@@ -0,0 +1,112 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use comfy_table::CellAlignment;
+use comfy_table::ContentArrangement;
+use comfy_table::Table;
+use comfy_table::presets;
+
+use crate::DataType;
+use crate::Error;
+use crate::ResultSet;
+
+impl ResultSet {
+    /// Render the result set as a text table, with a header of the field names.
+    ///
+    /// Values are shown in their [`Display`](std::fmt::Display) form, with numbers aligned to the
+    /// right. The table keeps the natural width of its cells; see
+    /// [`to_table_string_with_width`](Self::to_table_string_with_width) to fit a terminal.
+    pub fn to_table_string(&self) -> Result<String, Error> {
+        self.render_table(None)
+    }
+
+    /// Render the result set as a text table no wider than `width` columns, wrapping the content
+    /// of cells as needed.
+    pub fn to_table_string_with_width(&self, width: u16) -> Result<String, Error> {
+        self.render_table(Some(width))
+    }
+
+    fn render_table(&self, width: Option<u16>) -> Result<String, Error> {
+        let fields = self.schema().fields();
+
+        let mut table = Table::new();
+        table.load_preset(presets::UTF8_FULL_CONDENSED);
+        if let Some(width) = width {
+            table
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_width(width);
+        }
+        table.set_header(fields.iter().map(|field| field.name()));
+        for row in self.rows() {
+            table.add_row(row?.iter().map(|value| value.to_string()));
+        }
+
+        for (i, field) in fields.iter().enumerate() {
+            let numeric = matches!(
+                field.data_type(),
+                DataType::Int | DataType::UInt | DataType::Float | DataType::Decimal
+            );
+            if let Some(column) = table.column_mut(i) {
+                if numeric {
+                    column.set_cell_alignment(CellAlignment::Right);
+                }
+            }
+        }
+        Ok(table.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ResultSet;
+    use crate::protocol::StatementResultSet;
+
+    fn test_result_set() -> ResultSet {
+        let result_set: StatementResultSet = serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "fields": [
+                    {"name": "id", "data_type": "int"},
+                    {"name": "name", "data_type": "string"},
+                ],
+                "num_rows": 2,
+            },
+            "format": "json",
+            "rows": [["1", "alpha"], ["20", null]],
+        }))
+        .unwrap();
+        ResultSet::from_statement_result_set(result_set)
+    }
+
+    #[test]
+    fn test_to_table_string() {
+        assert_eq!(
+            test_result_set().to_table_string().unwrap(),
+            [
+                "┌────┬───────┐",
+                "│ id ┆ name  │",
+                "╞════╪═══════╡",
+                "│  1 ┆ alpha │",
+                "│ 20 ┆ NULL  │",
+                "└────┴───────┘",
+            ]
+            .join("\n")
+        );
+
+        let table = test_result_set().to_table_string_with_width(10).unwrap();
+        assert!(
+            table.lines().all(|line| line.chars().count() <= 10),
+            "{table}"
+        );
+    }
+}
@@ -14,6 +14,8 @@
 
 use std::fmt;
 
+use uuid::Uuid;
+
 /// ErrorKind is all kinds of Error of ScopeDB client.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -24,6 +26,46 @@ pub enum ErrorKind {
 
     /// The config for ScopeDB client is invalid.
     ConfigInvalid,
+
+    /// The server redirected the request, and the client is configured not to follow it.
+    ///
+    /// The redirect target is available in the `location` context.
+    Redirected,
+
+    /// The operation timed out on the client side.
+    Timeout(TimeoutOrigin),
+
+    /// The statement failed or was cancelled on the server.
+    ///
+    /// Details are available through [`Error::server_error`].
+    StatementFailed,
+
+    /// The request was rejected by a [`CircuitBreaker`] without being sent, because the server
+    /// kept failing recently.
+    ///
+    /// [`CircuitBreaker`]: crate::CircuitBreaker
+    CircuitOpen,
+
+    /// A [`RowStream`] produced more rows or bytes than its configured budget, or a statement
+    /// result exceeded the limits set with [`Statement::with_max_rows`] or
+    /// [`Statement::with_max_result_bytes`].
+    ///
+    /// [`Statement::with_max_rows`]: crate::Statement::with_max_rows
+    /// [`Statement::with_max_result_bytes`]: crate::Statement::with_max_result_bytes
+    /// [`RowStream`]: crate::RowStream
+    ResultBudgetExceeded,
+}
+
+/// TimeoutOrigin tells which client-side limit a timeout came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimeoutOrigin {
+    /// The HTTP request exceeded the timeout configured on the client.
+    Client,
+    /// The statement did not terminate within the deadline of the client while being waited on,
+    /// see [`PollPolicy::with_deadline`](crate::PollPolicy::with_deadline).
+    ///
+    /// The statement may still be running on the server.
+    Deadline,
 }
 
 impl ErrorKind {
@@ -44,6 +86,11 @@ impl From<ErrorKind> for &'static str {
         match v {
             ErrorKind::Unexpected => "Unexpected",
             ErrorKind::ConfigInvalid => "ConfigInvalid",
+            ErrorKind::Redirected => "Redirected",
+            ErrorKind::Timeout(_) => "Timeout",
+            ErrorKind::StatementFailed => "StatementFailed",
+            ErrorKind::CircuitOpen => "CircuitOpen",
+            ErrorKind::ResultBudgetExceeded => "ResultBudgetExceeded",
         }
     }
 }
@@ -80,6 +127,42 @@ impl fmt::Display for ErrorStatus {
     }
 }
 
+/// ServerError is a statement failure reported by the server, as opposed to transport or client
+/// errors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerError {
+    statement_id: Uuid,
+    message: String,
+    code: Option<String>,
+}
+
+impl ServerError {
+    pub(crate) fn new(statement_id: Uuid, message: String, code: Option<String>) -> Self {
+        Self {
+            statement_id,
+            message,
+            code,
+        }
+    }
+
+    /// The id of the failed statement.
+    pub fn statement_id(&self) -> Uuid {
+        self.statement_id
+    }
+
+    /// The failure message reported by the server.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The error code reported by the server, if any.
+    ///
+    /// The `code` field is unconfirmed: no server is known to send it yet, so expect `None`.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+}
+
 pub struct Error {
     kind: ErrorKind,
     message: String,
@@ -88,6 +171,7 @@ pub struct Error {
     context: Vec<(&'static str, String)>,
 
     source: Option<anyhow::Error>,
+    server_error: Option<ServerError>,
 }
 
 impl fmt::Display for Error {
@@ -130,6 +214,7 @@ impl fmt::Debug for Error {
             de.field("status", &self.status);
             de.field("context", &self.context);
             de.field("source", &self.source);
+            de.field("server_error", &self.server_error);
             return de.finish();
         }
 
@@ -172,6 +257,7 @@ impl Error {
             status: ErrorStatus::Permanent,
             context: Vec::default(),
             source: None,
+            server_error: None,
         }
     }
 
@@ -193,6 +279,12 @@ impl Error {
         self
     }
 
+    /// Attach the statement failure reported by the server.
+    pub(crate) fn set_server_error(mut self, server_error: ServerError) -> Self {
+        self.server_error = Some(server_error);
+        self
+    }
+
     /// Set permanent status for error.
     pub fn set_permanent(mut self) -> Self {
         self.status = ErrorStatus::Permanent;
@@ -220,6 +312,19 @@ impl Error {
         self.kind
     }
 
+    /// Return the statement failure reported by the server, if this error is one.
+    pub fn server_error(&self) -> Option<&ServerError> {
+        self.server_error.as_ref()
+    }
+
+    /// Return the context value of the given key, if any.
+    pub fn context(&self, key: &str) -> Option<&str> {
+        self.context
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
     /// Check if this error is temporary.
     pub fn is_temporary(&self) -> bool {
         self.status == ErrorStatus::Temporary
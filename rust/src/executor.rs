@@ -0,0 +1,145 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use mea::semaphore::Semaphore;
+
+use crate::Client;
+use crate::Error;
+use crate::ErrorKind;
+use crate::ResultSet;
+use crate::Statement;
+
+/// A queue that runs at most `max_concurrency` statements against the server at once.
+///
+/// Statements beyond the limit wait in submission order until a running one completes. Clones
+/// share the same limit.
+#[derive(Debug, Clone)]
+pub struct StatementExecutor {
+    client: Client,
+    max_concurrency: usize,
+    semaphore: Arc<Semaphore>,
+    closed: Arc<AtomicBool>,
+}
+
+impl StatementExecutor {
+    pub(crate) fn new(client: Client, max_concurrency: usize) -> Self {
+        let max_concurrency = max_concurrency.max(1);
+        Self {
+            client,
+            max_concurrency,
+            semaphore: Arc::new(Semaphore::new(max_concurrency)),
+            closed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Create a statement that can be passed to [`execute`](Self::execute).
+    pub fn statement(&self, statement: impl Into<String>) -> Statement {
+        self.client.statement(statement.into())
+    }
+
+    /// Run the statement to completion once a slot is available.
+    ///
+    /// Fails without running the statement if the executor has been shut down.
+    pub async fn execute(&self, statement: Statement) -> Result<ResultSet, Error> {
+        self.check_open()?;
+        let _permit = self.semaphore.acquire(1).await;
+        // Shutdown may have started while this statement was queued; it is drained all the
+        // same, since the caller is still waiting for it.
+        statement.execute().await
+    }
+
+    /// Stop accepting statements, and wait for the running and queued ones to complete.
+    pub async fn shutdown(&self) {
+        self.closed.store(true, Ordering::Release);
+        let _permits = self.semaphore.acquire(self.max_concurrency).await;
+    }
+
+    fn check_open(&self) -> Result<(), Error> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                "statement executor has been shut down".to_string(),
+            )
+            .set_permanent());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use crate::Client;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_executor_limits_concurrency() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(testing::finished(testing::result_set(
+                        &[("v", "int")],
+                        json!([["1"]]),
+                    )))
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let executor = client.executor(2);
+        let tasks = (0..4)
+            .map(|_| {
+                let executor = executor.clone();
+                tokio::spawn(async move { executor.execute(executor.statement("SELECT 1")).await })
+            })
+            .collect::<Vec<_>>();
+
+        // Give the tasks a chance to queue before shutting down.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let start = tokio::time::Instant::now();
+        executor.shutdown().await;
+        let elapsed = start.elapsed();
+        // Four statements with two slots take two rounds.
+        assert!(elapsed >= Duration::from_millis(150), "{elapsed:?}");
+
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        let err = executor
+            .execute(executor.statement("SELECT 1"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("shut down"));
+    }
+}
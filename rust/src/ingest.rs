@@ -0,0 +1,159 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Client;
+use crate::Error;
+use crate::ErrorKind;
+use crate::IngestData;
+use crate::IngestResult;
+use crate::IngestType;
+use crate::protocol::IngestRequest;
+
+/// A single ingest request into a table, built fluently and sent with [`send`](Self::send).
+///
+/// The data is inserted by running the transform statement, e.g.,
+/// `SELECT $0["ts"]::timestamp AS ts`, followed by `INSERT INTO` the table.
+#[derive(Debug, Clone)]
+pub struct IngestBuilder {
+    client: Client,
+    table: String,
+    data: Option<IngestData>,
+    transform: Option<String>,
+    ty: IngestType,
+    idempotency_key: Option<String>,
+}
+
+impl IngestBuilder {
+    pub(crate) fn new(client: Client, table: String) -> Self {
+        Self {
+            client,
+            table,
+            data: None,
+            transform: None,
+            ty: IngestType::default(),
+            idempotency_key: None,
+        }
+    }
+
+    /// Set the rows to ingest.
+    pub fn data(mut self, data: IngestData) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Set the statement that transforms each row before it is inserted into the table.
+    pub fn transform(mut self, transform: impl Into<String>) -> Self {
+        self.transform = Some(transform.into());
+        self
+    }
+
+    /// Set whether the rows are committed before the request returns, or buffered by the
+    /// server. Defaults to [`IngestType::Committed`].
+    pub fn ingest_type(mut self, ty: IngestType) -> Self {
+        self.ty = ty;
+        self
+    }
+
+    /// Set a key identifying the request, sent as the `Idempotency-Key` header.
+    ///
+    /// The key is a client-side hint: it marks the request as safe to repeat, so that a
+    /// [`RetryPolicy`](crate::RetryPolicy) retries it. The server is not known to deduplicate
+    /// requests by the key, so a retried request may insert its rows twice if the server acted
+    /// on an attempt whose response was lost.
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// The statement run to insert the data.
+    pub fn statement(&self) -> Option<String> {
+        let transform = self.transform.as_deref()?;
+        Some(format!("{transform} INSERT INTO {}", self.table))
+    }
+
+    pub async fn send(self) -> Result<IngestResult, Error> {
+        let Some(statement) = self.statement() else {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("no transform statement to ingest into {}", self.table),
+            ));
+        };
+        let Some(data) = self.data else {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("no data to ingest into {}", self.table),
+            ));
+        };
+        self.client
+            .insert_request(IngestRequest {
+                ty: self.ty,
+                data,
+                statement,
+                idempotency_key: self.idempotency_key,
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_json;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use super::*;
+    use crate::retry::IDEMPOTENCY_KEY;
+
+    #[tokio::test]
+    async fn test_send() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(body_json(json!({
+                "type": "buffered",
+                "data": {"format": "json", "rows": "{\"k\":1}"},
+                "statement": "SELECT $0[\"k\"]::int AS k INSERT INTO db.sch.t",
+            })))
+            .and(header(IDEMPOTENCY_KEY, "batch-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": 1})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let result = client
+            .ingest_into("db.sch.t")
+            .data(IngestData::from_json_rows(&[json!({"k": 1})]).unwrap())
+            .transform(r#"SELECT $0["k"]::int AS k"#)
+            .ingest_type(IngestType::Buffered)
+            .idempotency_key("batch-1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(result.num_rows_inserted, 1);
+
+        let err = client
+            .ingest_into("db.sch.t")
+            .transform("SELECT $0")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no data to ingest into db.sch.t"));
+    }
+}
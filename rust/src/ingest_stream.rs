@@ -16,6 +16,7 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 
@@ -234,6 +235,7 @@ pub struct IngestStream {
     task: Mutex<Option<JoinHandle<()>>>,
     fatal: Arc<Mutex<Option<FatalState>>>,
     pending_bytes: Arc<PendingBytesBudget>,
+    pub(crate) num_rows_inserted: Arc<AtomicI64>,
 }
 
 impl IngestStream {
@@ -249,6 +251,7 @@ impl IngestStream {
         let (tx, rx) = mpsc::bounded(channel_capacity.max(1));
         let fatal = Arc::new(Mutex::new(None));
         let pending_bytes = Arc::new(PendingBytesBudget::new(max_pending_bytes.max(1)));
+        let num_rows_inserted = Arc::new(AtomicI64::new(0));
         let task = tokio::spawn(run_batch_worker(
             rx,
             batch_bytes,
@@ -256,10 +259,18 @@ impl IngestStream {
             retry,
             fatal.clone(),
             pending_bytes.clone(),
-            move |rows| {
-                let client = client.clone();
-                let statement = statement.clone();
-                Box::pin(async move { client.insert(rows, statement).await })
+            {
+                let num_rows_inserted = num_rows_inserted.clone();
+                move |rows| {
+                    let client = client.clone();
+                    let statement = statement.clone();
+                    let num_rows_inserted = num_rows_inserted.clone();
+                    Box::pin(async move {
+                        let result = client.insert(rows, statement).await?;
+                        num_rows_inserted.fetch_add(result.num_rows_inserted, Ordering::AcqRel);
+                        Ok(result)
+                    })
+                }
             },
         ));
 
@@ -268,11 +279,11 @@ impl IngestStream {
             task: Mutex::new(Some(task)),
             fatal,
             pending_bytes,
+            num_rows_inserted,
         }
     }
 
     pub async fn send<T: Serialize>(&self, record: &T) -> Result<(), Error> {
-        self.check_fatal().await?;
         let payload = serde_json::to_string(record).map_err(|err| {
             Error::new(
                 ErrorKind::Unexpected,
@@ -280,7 +291,12 @@ impl IngestStream {
             )
             .set_source(err)
         })?;
+        self.send_payload(payload).await
+    }
 
+    /// Buffer a record already serialized as a JSON object.
+    pub(crate) async fn send_payload(&self, payload: String) -> Result<(), Error> {
+        self.check_fatal().await?;
         let reserved = match self.pending_bytes.acquire(buffered_bytes(&payload)).await {
             Ok(reserved) => reserved,
             Err(PendingBytesAcquireError::Closed) => {
@@ -329,6 +345,10 @@ impl IngestStream {
     }
 
     pub async fn shutdown(self) -> Result<Option<IngestResult>, Error> {
+        self.close().await
+    }
+
+    pub(crate) async fn close(&self) -> Result<Option<IngestResult>, Error> {
         let (tx, rx) = oneshot::channel();
         if self.tx.send(BatchCommand::Shutdown(tx)).await.is_err() {
             return Err(self.closed_or_fatal_error().await);
@@ -0,0 +1,145 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A quote- and comment-aware tokenizer for ScopeQL, shared by the client-side passes over
+//! statement text.
+
+/// The kind of a [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    /// A string literal or quoted identifier, including its quotes.
+    Quoted,
+    /// A `--` comment, up to but not including the line break.
+    LineComment,
+    /// A `/* */` comment.
+    BlockComment,
+    /// A run of whitespace.
+    Whitespace,
+    /// A run of alphanumeric characters, `_` and `$`, such as a keyword, a number or a
+    /// placeholder.
+    Word,
+    /// Any other single character, such as `;`.
+    Punct,
+}
+
+/// A token of a statement, borrowing its text from the statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// The byte offset of the token in the statement.
+    pub start: usize,
+}
+
+impl Token<'_> {
+    pub(crate) fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Whether the token is neither whitespace nor a comment.
+    pub(crate) fn is_code(&self) -> bool {
+        !matches!(
+            self.kind,
+            TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment
+        )
+    }
+}
+
+/// Split `text` into tokens. Quotes are `'`, `"` and `` ` ``, with `\` escaping the next
+/// character; an unterminated quote or comment extends to the end of the text.
+pub(crate) fn tokenize(text: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        let rest = &text[start..];
+        let first = rest.chars().next()?;
+        let (kind, len) = match first {
+            quote @ ('\'' | '"' | '`') => {
+                let mut escaped = false;
+                let len = rest[1..]
+                    .char_indices()
+                    .find(|&(_, c)| {
+                        let found = !escaped && c == quote;
+                        escaped = !escaped && c == '\\';
+                        found
+                    })
+                    .map_or(rest.len(), |(i, _)| i + 2);
+                (TokenKind::Quoted, len)
+            }
+            '-' if rest.starts_with("--") => (
+                TokenKind::LineComment,
+                rest.find('\n').unwrap_or(rest.len()),
+            ),
+            '/' if rest.starts_with("/*") => (
+                TokenKind::BlockComment,
+                rest[2..].find("*/").map_or(rest.len(), |i| i + 4),
+            ),
+            c if c.is_whitespace() => (
+                TokenKind::Whitespace,
+                rest.find(|c: char| !c.is_whitespace())
+                    .unwrap_or(rest.len()),
+            ),
+            c if is_word_char(c) => (
+                TokenKind::Word,
+                rest.find(|c| !is_word_char(c)).unwrap_or(rest.len()),
+            ),
+            c => (TokenKind::Punct, c.len_utf8()),
+        };
+        let token = Token {
+            kind,
+            text: &rest[..len],
+            start,
+        };
+        start += len;
+        Some(token)
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("FROM t -- c;\nWHERE s = 'it\\'s' /* x */;$1 \"a")
+            .map(|token| (token.kind, token.text))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            [
+                (TokenKind::Word, "FROM"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Word, "t"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::LineComment, "-- c;"),
+                (TokenKind::Whitespace, "\n"),
+                (TokenKind::Word, "WHERE"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Word, "s"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Punct, "="),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Quoted, "'it\\'s'"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::BlockComment, "/* x */"),
+                (TokenKind::Punct, ";"),
+                (TokenKind::Word, "$1"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Quoted, "\"a"),
+            ]
+        );
+    }
+}
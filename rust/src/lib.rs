@@ -12,24 +12,81 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+// Lets the code generated by the derive macros refer to this crate by name, in its own tests too.
+extern crate self as scopedb_client;
+
+#[cfg(feature = "arrow")]
+mod arrow;
+mod cache;
+mod circuit_breaker;
 mod client;
+mod compression;
+mod csv;
+#[cfg(feature = "polars")]
+mod dataframe;
+#[cfg(feature = "display")]
+mod display;
 mod error;
+mod executor;
+mod ingest;
 mod ingest_stream;
+mod lexer;
+mod middleware;
+mod plan;
+mod poll;
+mod prepared;
 mod protocol;
+#[cfg(feature = "record")]
+mod record;
 mod result;
+mod retry;
+mod row;
+mod script;
 mod statement;
+mod stream;
 mod table;
+mod template;
+mod writer;
+
+#[cfg(test)]
+mod testing;
 
+pub use cache::ResultCache;
+pub use circuit_breaker::CircuitBreaker;
 pub use client::Client;
+pub use client::ClientBuilder;
+pub use client::RedirectPolicy;
+pub use compression::Compression;
+pub use csv::CsvOptions;
+pub use csv::QuoteStyle;
 pub use error::Error;
 pub use error::ErrorKind;
+pub use error::ServerError;
+pub use error::TimeoutOrigin;
+pub use executor::StatementExecutor;
+pub use ingest::IngestBuilder;
 pub use ingest_stream::IngestStream;
 pub use ingest_stream::IngestStreamBuilder;
+pub use middleware::BoxFuture;
+pub use middleware::Middleware;
+pub use middleware::Next;
+pub use plan::PlanNode;
+pub use plan::QueryPlan;
+pub use poll::PollPolicy;
+pub use prepared::PreparedStatement;
 pub use protocol::DataType;
+pub use protocol::HealthStatus;
+pub use protocol::IngestData;
 pub use protocol::IngestResult;
+pub use protocol::IngestType;
+pub use protocol::Priority;
+pub use protocol::ResultFormat;
 pub use protocol::StatementCancelResult;
+pub use protocol::StatementEntry;
 pub use protocol::StatementEstimatedProgress;
+pub use protocol::StatementFilter;
 pub use protocol::StatementProgress;
+pub use protocol::StatementState;
 pub use protocol::StatementStatus;
 pub use protocol::StatementStatusCancelled;
 pub use protocol::StatementStatusFailed;
@@ -37,9 +94,31 @@ pub use protocol::StatementStatusFinished;
 pub use protocol::StatementStatusPending;
 pub use protocol::StatementStatusRunning;
 pub use result::FieldSchema;
+pub use result::ParseMode;
+pub use result::ParseWarning;
 pub use result::ResultSet;
+pub use result::Rows;
 pub use result::Schema;
 pub use result::Value;
+pub use retry::RetryPolicy;
+pub use row::ColumnIndex;
+pub use row::FromRow;
+pub use row::FromValue;
+pub use row::Row;
+#[cfg(feature = "derive")]
+pub use scopedb_derive::FromRow;
+pub use statement::QueryOutcome;
 pub use statement::Statement;
+pub use statement::StatementCheckpoint;
 pub use statement::StatementHandle;
+pub use stream::RowStream;
 pub use table::Table;
+#[cfg(feature = "arrow")]
+pub use writer::ArrowSink;
+pub use writer::Writer;
+pub use writer::WriterSink;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::row::ColumnPositions;
+}
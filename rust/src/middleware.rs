@@ -0,0 +1,224 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::Client;
+use crate::Error;
+
+/// An owned, dynamically typed [`Future`] returned by [`Middleware::handle`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A layer that every HTTP request sent by the [`Client`] flows through.
+///
+/// Middlewares are registered with [`ClientBuilder::with_middleware`] and run in the order they
+/// are added: the first one added sees the request first and the response last. A middleware can
+/// inspect or modify the request, short-circuit with an error, or call [`Next::run`] to pass the
+/// request down the stack, possibly more than once.
+///
+/// Each hop of a followed redirect is a separate request through the stack.
+///
+/// [`ClientBuilder::with_middleware`]: crate::ClientBuilder::with_middleware
+pub trait Middleware: Send + Sync + 'static {
+    fn handle<'a>(
+        &'a self,
+        request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response, Error>>;
+}
+
+/// The remaining middlewares of the stack, followed by the actual HTTP transport.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a Client,
+    middlewares: &'a [Arc<dyn Middleware>],
+    message: &'a (dyn Fn() -> String + Send + Sync),
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        client: &'a Client,
+        middlewares: &'a [Arc<dyn Middleware>],
+        message: &'a (dyn Fn() -> String + Send + Sync),
+    ) -> Self {
+        Self {
+            client,
+            middlewares,
+            message,
+        }
+    }
+
+    /// Pass the request to the next middleware, or send it if this is the end of the stack.
+    pub fn run(self, request: reqwest::Request) -> BoxFuture<'a, Result<reqwest::Response, Error>> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => middleware.handle(
+                request,
+                Next {
+                    middlewares,
+                    ..self
+                },
+            ),
+            None => Box::pin(self.client.transport(request, self.message)),
+        }
+    }
+}
+
+impl fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Next")
+            .field("middlewares", &self.middlewares.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The middlewares configured on a client.
+#[derive(Clone, Default)]
+pub(crate) struct Middlewares(Arc<[Arc<dyn Middleware>]>);
+
+impl Middlewares {
+    pub(crate) fn new(middlewares: Vec<Arc<dyn Middleware>>) -> Self {
+        Self(middlewares.into())
+    }
+
+    pub(crate) fn as_slice(&self) -> &[Arc<dyn Middleware>] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Middlewares {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Middlewares").field(&self.0.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::headers;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use super::*;
+    use crate::ErrorKind;
+    use crate::testing;
+
+    struct Trace {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Middleware for Trace {
+        fn handle<'a>(
+            &'a self,
+            mut request: reqwest::Request,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, Result<reqwest::Response, Error>> {
+            Box::pin(async move {
+                self.log
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} request", self.name));
+                request
+                    .headers_mut()
+                    .append("x-middleware", self.name.parse().unwrap());
+                let response = next.run(request).await;
+                self.log
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} response", self.name));
+                response
+            })
+        }
+    }
+
+    struct Reject;
+
+    impl Middleware for Reject {
+        fn handle<'a>(
+            &'a self,
+            _: reqwest::Request,
+            _: Next<'a>,
+        ) -> BoxFuture<'a, Result<reqwest::Response, Error>> {
+            Box::pin(async { Err(Error::new(ErrorKind::Unexpected, "rejected".to_string())) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(headers("x-middleware", vec!["outer", "inner"]))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let log = Arc::new(Mutex::new(vec![]));
+        let client = Client::builder(server.uri())
+            .with_middleware(Trace {
+                name: "outer",
+                log: log.clone(),
+            })
+            .with_middleware(Trace {
+                name: "inner",
+                log: log.clone(),
+            })
+            .build()
+            .unwrap();
+        client
+            .statement("SELECT 1".to_string())
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "outer request",
+                "inner request",
+                "inner response",
+                "outer response"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_middleware_short_circuit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .with_middleware(Reject)
+            .build()
+            .unwrap();
+        let err = client.health().await.unwrap_err();
+        assert!(err.to_string().contains("rejected"));
+    }
+}
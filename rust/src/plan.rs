@@ -0,0 +1,212 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Error;
+use crate::ErrorKind;
+use crate::ResultSet;
+
+/// The plan of a statement, as returned by [`Client::explain`](crate::Client::explain).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPlan {
+    /// The top-level operators of the plan, usually a single one.
+    pub roots: Vec<PlanNode>,
+    /// The plan as printed by the server.
+    pub text: String,
+}
+
+/// An operator of a [`QueryPlan`] along with its inputs.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanNode {
+    /// The name of the operator, e.g., `TableScan`.
+    pub operator: String,
+    /// The line describing the operator, without its indentation.
+    pub details: String,
+    /// The number of rows the operator is estimated to produce, if the plan says.
+    pub estimated_rows: Option<u64>,
+    pub children: Vec<PlanNode>,
+}
+
+impl QueryPlan {
+    /// Parse the plan from the result of an `EXPLAIN` statement, whose first column holds the
+    /// lines of the plan.
+    ///
+    /// Each line describes an operator, and the operators indented under it are its inputs.
+    /// Indentation may be drawn with spaces, tabs or tree characters such as `├─`, `└─` and
+    /// `->`.
+    pub fn from_result_set(result_set: &ResultSet) -> Result<Self, Error> {
+        if result_set.schema().fields().is_empty() {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                "explain result has no columns".to_string(),
+            ));
+        }
+        let lines = result_set.column::<Option<String>>(0)?;
+        let text = lines.into_iter().flatten().collect::<Vec<_>>().join("\n");
+        Ok(Self::parse(text))
+    }
+
+    /// Parse the plan from its text, one operator per line.
+    pub fn parse(text: String) -> Self {
+        let mut roots = vec![];
+        // The chain of operators from a root to the last parsed one, with their depths.
+        let mut stack: Vec<(usize, PlanNode)> = vec![];
+        for line in text.lines() {
+            let Some((depth, node)) = parse_line(line) else {
+                continue;
+            };
+            pop_until(&mut stack, &mut roots, depth);
+            stack.push((depth, node));
+        }
+        pop_until(&mut stack, &mut roots, 0);
+        Self { roots, text }
+    }
+
+    /// Iterate over all operators of the plan, parents before their inputs.
+    pub fn nodes(&self) -> impl Iterator<Item = &PlanNode> {
+        let mut pending = self.roots.iter().rev().collect::<Vec<_>>();
+        std::iter::from_fn(move || {
+            let node = pending.pop()?;
+            pending.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+}
+
+/// Attach the operators on the stack at `depth` or deeper to their parents.
+fn pop_until(stack: &mut Vec<(usize, PlanNode)>, roots: &mut Vec<PlanNode>, depth: usize) {
+    while stack.last().is_some_and(|(d, _)| *d >= depth) {
+        let (_, node) = stack.pop().unwrap();
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<(usize, PlanNode)> {
+    let label = line.trim_start_matches(|c: char| {
+        c.is_whitespace() || matches!(c, '│' | '├' | '└' | '─' | '|' | '+' | '`')
+    });
+    let label = label.strip_prefix("->").unwrap_or(label).trim_start();
+    // The width of the indentation, in characters.
+    let depth = line[..line.len() - label.len()].chars().count();
+    let label = label.trim_end();
+    if label.is_empty() {
+        return None;
+    }
+    let operator = label
+        .split(|c: char| c.is_whitespace() || matches!(c, ':' | '(' | '[' | '{'))
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    Some((
+        depth,
+        PlanNode {
+            operator,
+            details: label.to_string(),
+            estimated_rows: estimated_rows(label),
+            children: vec![],
+        },
+    ))
+}
+
+/// The keys of row estimates in plan lines.
+const ESTIMATE_KEYS: [&str; 3] = ["rows", "est_rows", "estimated_rows"];
+
+/// Find an estimate such as `rows=100`, `est_rows: 100` or `estimated_rows=1,000` in the line.
+///
+/// A key only counts as a whole word, so a column such as `total_rows` is no estimate.
+fn estimated_rows(label: &str) -> Option<u64> {
+    label.match_indices("rows").find_map(|(i, _)| {
+        let end = i + "rows".len();
+        let key = label[..end]
+            .rsplit(|c: char| !c.is_alphanumeric() && c != '_')
+            .next()?;
+        if !ESTIMATE_KEYS.contains(&key) {
+            return None;
+        }
+        let rest = label[end..].trim_start();
+        let rest = rest.strip_prefix(['=', ':'])?.trim_start();
+        let digits = rest
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || matches!(c, ',' | '_'))
+            .filter(char::is_ascii_digit)
+            .collect::<String>();
+        digits.parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let plan = QueryPlan::parse(
+            [
+                "Projection: a, b",
+                "  Filter: a > 1 (rows=10)",
+                "    TableScan: t [est_rows: 1,000]",
+                "  Values (rows=2)",
+            ]
+            .join("\n"),
+        );
+        assert_eq!(plan.roots.len(), 1);
+        let root = &plan.roots[0];
+        assert_eq!(root.operator, "Projection");
+        assert_eq!(root.estimated_rows, None);
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].details, "Filter: a > 1 (rows=10)");
+        assert_eq!(root.children[0].estimated_rows, Some(10));
+        assert_eq!(root.children[0].children[0].operator, "TableScan");
+        assert_eq!(root.children[0].children[0].estimated_rows, Some(1000));
+        assert_eq!(root.children[1].estimated_rows, Some(2));
+
+        let operators = plan
+            .nodes()
+            .map(|n| n.operator.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(operators, ["Projection", "Filter", "TableScan", "Values"]);
+    }
+
+    #[test]
+    fn test_estimated_rows_whole_key() {
+        assert_eq!(estimated_rows("Filter: total_rows = 10"), None);
+        assert_eq!(estimated_rows("Filter: rowsum = 1 (rows: 5)"), Some(5));
+        assert_eq!(estimated_rows("Scan (estimated_rows=1_000)"), Some(1000));
+        assert_eq!(estimated_rows("Scan (my_est_rows=3)"), None);
+    }
+
+    #[test]
+    fn test_parse_tree_characters() {
+        let plan = QueryPlan::parse(
+            [
+                "Aggregate",
+                "├─ Scan a",
+                "└─ Join",
+                "   └─ Scan b",
+                "",
+                "Limit",
+            ]
+            .join("\n"),
+        );
+        assert_eq!(plan.roots.len(), 2);
+        let aggregate = &plan.roots[0];
+        assert_eq!(aggregate.children.len(), 2);
+        assert_eq!(aggregate.children[1].children[0].details, "Scan b");
+        assert_eq!(plan.roots[1].operator, "Limit");
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The shortest interval between polls, so that polling never turns into a busy loop.
+const MIN_INTERVAL: Duration = Duration::from_millis(5);
+
+/// How often to poll the status of a statement while waiting for it to terminate.
+///
+/// Polling starts at `initial_interval` and grows by `factor` after each poll, up to
+/// `max_interval`. With a `deadline`, waiting fails with
+/// [`TimeoutOrigin::Deadline`](crate::TimeoutOrigin::Deadline) once the deadline has passed since
+/// it started; the statement keeps running on the server.
+///
+/// With `long_poll`, each poll asks the server to hold the request until the status changes, for
+/// up to the given duration, and the interval only applies to the time left after a poll. Long
+/// polling is not part of the confirmed server API yet: a server without it answers right away,
+/// so polling falls back to the intervals. The timeout of the HTTP client, if any, should exceed
+/// the long poll duration.
+///
+/// Intervals below 5ms are taken as 5ms, also when a policy is deserialized.
+///
+/// By default, polling starts at 5ms and doubles up to 1s, without a deadline or long polling. Set
+/// a policy for all statements of a client with [`ClientBuilder::poll_policy`], or for a single
+/// statement with [`Statement::with_poll_policy`].
+///
+/// [`ClientBuilder::poll_policy`]: crate::ClientBuilder::poll_policy
+/// [`Statement::with_poll_policy`]: crate::Statement::with_poll_policy
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(from = "PollPolicyFields")]
+pub struct PollPolicy {
+    initial_interval: Duration,
+    max_interval: Duration,
+    factor: f64,
+    deadline: Option<Duration>,
+    long_poll: Option<Duration>,
+}
+
+/// The fields of a serialized [`PollPolicy`], validated by its setters on deserialization.
+#[derive(Deserialize)]
+struct PollPolicyFields {
+    initial_interval: Duration,
+    max_interval: Duration,
+    factor: f64,
+    deadline: Option<Duration>,
+    long_poll: Option<Duration>,
+}
+
+impl From<PollPolicyFields> for PollPolicy {
+    fn from(fields: PollPolicyFields) -> Self {
+        Self {
+            deadline: fields.deadline,
+            long_poll: fields.long_poll,
+            ..Self::default()
+        }
+        .with_initial_interval(fields.initial_interval)
+        .with_max_interval(fields.max_interval)
+        .with_factor(fields.factor)
+    }
+}
+
+impl Default for PollPolicy {
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_millis(5),
+            max_interval: Duration::from_secs(1),
+            factor: 2.0,
+            deadline: None,
+            long_poll: None,
+        }
+    }
+}
+
+impl PollPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Poll with a fixed interval.
+    pub fn fixed(interval: Duration) -> Self {
+        let interval = interval.max(MIN_INTERVAL);
+        Self {
+            initial_interval: interval,
+            max_interval: interval,
+            factor: 1.0,
+            deadline: None,
+            long_poll: None,
+        }
+    }
+
+    pub fn with_initial_interval(mut self, initial_interval: Duration) -> Self {
+        self.initial_interval = initial_interval.max(MIN_INTERVAL);
+        self
+    }
+
+    pub fn with_max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval.max(MIN_INTERVAL);
+        self
+    }
+
+    /// Set the factor by which the interval grows after each poll; factors below 1 are taken
+    /// as 1.
+    pub fn with_factor(mut self, factor: f64) -> Self {
+        self.factor = if factor >= 1.0 { factor } else { 1.0 };
+        self
+    }
+
+    /// Give up waiting once `deadline` has passed.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Ask the server to hold each poll for up to `wait` until the status changes.
+    ///
+    /// This sends the unconfirmed `wait_ms` query parameter, which servers may ignore.
+    pub fn with_long_poll(mut self, wait: Duration) -> Self {
+        self.long_poll = Some(wait);
+        self
+    }
+
+    pub fn initial_interval(&self) -> Duration {
+        self.initial_interval
+    }
+
+    pub fn max_interval(&self) -> Duration {
+        self.max_interval
+    }
+
+    pub fn factor(&self) -> f64 {
+        self.factor
+    }
+
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    pub fn long_poll(&self) -> Option<Duration> {
+        self.long_poll
+    }
+
+    /// The interval to wait after polling with `interval`.
+    pub(crate) fn next_interval(&self, interval: Duration) -> Duration {
+        Duration::try_from_secs_f64(interval.as_secs_f64() * self.factor)
+            .map_or(self.max_interval, |interval| {
+                interval.min(self.max_interval)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_interval() {
+        let policy = PollPolicy::new()
+            .with_initial_interval(Duration::from_millis(100))
+            .with_max_interval(Duration::from_millis(500))
+            .with_factor(3.0);
+        let intervals = std::iter::successors(Some(policy.initial_interval()), |interval| {
+            Some(policy.next_interval(*interval))
+        })
+        .take(4)
+        .map(|interval| interval.as_millis())
+        .collect::<Vec<_>>();
+        assert_eq!(intervals, [100, 300, 500, 500]);
+
+        let policy = PollPolicy::fixed(Duration::from_millis(50)).with_factor(0.5);
+        let interval = policy.next_interval(policy.initial_interval());
+        assert_eq!(interval, Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_min_interval() {
+        let policy = PollPolicy::new()
+            .with_initial_interval(Duration::ZERO)
+            .with_max_interval(Duration::ZERO);
+        assert_eq!(policy.initial_interval(), MIN_INTERVAL);
+        assert_eq!(
+            policy.next_interval(policy.initial_interval()),
+            MIN_INTERVAL
+        );
+        assert_eq!(
+            PollPolicy::fixed(Duration::ZERO).max_interval(),
+            MIN_INTERVAL
+        );
+
+        let mut value = serde_json::to_value(PollPolicy::new()).unwrap();
+        value["initial_interval"] = serde_json::to_value(Duration::ZERO).unwrap();
+        value["factor"] = serde_json::json!(0.0);
+        let policy = serde_json::from_value::<PollPolicy>(value).unwrap();
+        assert_eq!(policy.initial_interval(), MIN_INTERVAL);
+        assert_eq!(policy.factor(), 1.0);
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use crate::Client;
+use crate::Error;
+use crate::ErrorKind;
+use crate::ResultSet;
+use crate::Statement;
+use crate::Value;
+use crate::template::Placeholder;
+use crate::template::Template;
+
+/// A statement that is parsed once and executed repeatedly with different parameter values.
+///
+/// Created by [`Client::prepare`]. The protocol has no server-side prepared statements, so
+/// preparing happens on the client: the statement is parsed once into a template of its
+/// positional (`$1`) and named (`$name`) placeholders. Each execution checks its values against
+/// them and renders them into the template as ScopeQL literals, as [`Statement::bind`] does.
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    client: Client,
+    template: Template,
+    num_parameters: usize,
+    named_parameters: BTreeSet<String>,
+}
+
+impl PreparedStatement {
+    pub(crate) fn new(client: Client, statement: String) -> Self {
+        let template = Template::parse(statement);
+        let mut num_parameters = 0;
+        let mut named_parameters = BTreeSet::new();
+        for placeholder in template.placeholders() {
+            match placeholder {
+                Placeholder::Positional(n) => num_parameters = num_parameters.max(*n),
+                Placeholder::Named(name) => {
+                    named_parameters.insert(name.clone());
+                }
+            }
+        }
+        Self {
+            client,
+            template,
+            num_parameters,
+            named_parameters,
+        }
+    }
+
+    pub fn statement(&self) -> &str {
+        self.template.text()
+    }
+
+    /// The number of positional parameters, i.e., the highest `$n` in the statement.
+    pub fn num_parameters(&self) -> usize {
+        self.num_parameters
+    }
+
+    /// The names of the named parameters in the statement, in order.
+    pub fn named_parameters(&self) -> impl Iterator<Item = &str> {
+        self.named_parameters.iter().map(String::as_str)
+    }
+
+    /// Create a statement with `values` bound to the positional parameters, in order.
+    ///
+    /// Fails if the number of values does not match the statement, or if it has named parameters.
+    pub fn bind<V: Into<Value>>(
+        &self,
+        values: impl IntoIterator<Item = V>,
+    ) -> Result<Statement, Error> {
+        let values = values.into_iter().map(Into::into).collect();
+        self.bind_all(values, BTreeMap::new())
+    }
+
+    /// Create a statement with `entries` bound to the named parameters.
+    ///
+    /// Fails if any named parameter is left unbound or if an entry matches no parameter, or if the
+    /// statement has positional parameters.
+    pub fn bind_named<K: Into<String>, V: Into<Value>>(
+        &self,
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Statement, Error> {
+        let entries = entries
+            .into_iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+        self.bind_all(vec![], entries)
+    }
+
+    /// Execute the statement with `values` bound to the positional parameters, in order.
+    pub async fn execute<V: Into<Value>>(
+        &self,
+        values: impl IntoIterator<Item = V>,
+    ) -> Result<ResultSet, Error> {
+        self.bind(values)?.execute().await
+    }
+
+    /// Execute the statement with `entries` bound to the named parameters.
+    pub async fn execute_named<K: Into<String>, V: Into<Value>>(
+        &self,
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<ResultSet, Error> {
+        self.bind_named(entries)?.execute().await
+    }
+
+    fn bind_all(
+        &self,
+        values: Vec<Value>,
+        entries: BTreeMap<String, Value>,
+    ) -> Result<Statement, Error> {
+        let make_error = |message: String| {
+            Error::new(ErrorKind::Unexpected, message)
+                .with_context("statement", self.template.text())
+                .set_permanent()
+        };
+
+        if values.len() != self.num_parameters {
+            return Err(make_error(format!(
+                "expected {} values for positional parameters, got {}",
+                self.num_parameters,
+                values.len()
+            )));
+        }
+        if let Some(name) = self
+            .named_parameters
+            .iter()
+            .find(|name| !entries.contains_key(*name))
+        {
+            return Err(make_error(format!("named parameter ${name} is not bound")));
+        }
+        if let Some(name) = entries
+            .keys()
+            .find(|name| !self.named_parameters.contains(*name))
+        {
+            return Err(make_error(format!(
+                "statement has no named parameter ${name}"
+            )));
+        }
+
+        let statement = self.template.render(&values, &entries);
+        Ok(self.client.statement(statement))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_partial_json;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn test_placeholders() {
+        let client = Client::builder("http://localhost").build().unwrap();
+        let prepared = client.prepare(
+            "FROM t WHERE a = $2 AND b = $1 AND c = $name_1 \
+             AND d = '$3 it''s' AND e = \"$x\" -- $4\n\
+             AND f = $other /* $5 */"
+                .to_string(),
+        );
+        assert_eq!(prepared.num_parameters(), 2);
+        assert_eq!(
+            prepared.named_parameters().collect::<Vec<_>>(),
+            ["name_1", "other"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prepared_statement() {
+        let server = MockServer::start().await;
+        for id in [1, 2] {
+            Mock::given(method("POST"))
+                .and(path("/v1/statements"))
+                .and(body_partial_json(json!({
+                    "statement": format!("FROM t WHERE id = {id}"),
+                })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                    testing::result_set(&[("id", "int")], json!([[id.to_string()]])),
+                )))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let prepared = client.prepare("FROM t WHERE id = $1".to_string());
+        assert_eq!(prepared.num_parameters(), 1);
+        for id in [1, 2] {
+            let result_set = prepared.execute([id]).await.unwrap();
+            assert_eq!(result_set.column::<i64>("id").unwrap(), [id]);
+        }
+
+        let err = prepared.bind([1, 2]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected 1 values for positional parameters"),
+            "{err}"
+        );
+        let err = prepared.bind_named([("id", 1)]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected 1 values for positional parameters"),
+            "{err}"
+        );
+
+        let prepared = client.prepare("FROM t WHERE id = $id".to_string());
+        let err = prepared.bind_named([("other", 1)]).unwrap_err();
+        assert!(err.to_string().contains("$id is not bound"), "{err}");
+        let err = prepared.bind_named([("id", 1), ("other", 2)]).unwrap_err();
+        assert!(
+            err.to_string().contains("no named parameter $other"),
+            "{err}"
+        );
+    }
+}
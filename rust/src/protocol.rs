@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::str::FromStr;
 
@@ -25,6 +26,7 @@ use uuid::Uuid;
 use crate::Error;
 use crate::ErrorKind;
 use crate::ResultSet;
+use crate::TimeoutOrigin;
 
 #[derive(Debug, Clone)]
 pub enum Response<T> {
@@ -34,13 +36,55 @@ pub enum Response<T> {
 
 impl<T: DeserializeOwned> Response<T> {
     pub async fn from_http_response(r: reqwest::Response) -> Result<Self, Error> {
-        let make_error = |err| {
-            Error::new(ErrorKind::Unexpected, "failed to make response".to_string()).set_source(err)
+        Self::from_http_response_with_limit(r, None).await
+    }
+
+    /// Like [`from_http_response`](Self::from_http_response), but fails with
+    /// [`ErrorKind::ResultBudgetExceeded`] instead of reading a successful response body larger
+    /// than `max_bytes`.
+    pub async fn from_http_response_with_limit(
+        mut r: reqwest::Response,
+        max_bytes: Option<usize>,
+    ) -> Result<Self, Error> {
+        let make_error = |err: reqwest::Error| {
+            let message = "failed to make response".to_string();
+            if err.is_timeout() {
+                Error::new(ErrorKind::Timeout(TimeoutOrigin::Client), message)
+                    .set_source(err)
+                    .set_temporary()
+            } else {
+                Error::new(ErrorKind::Unexpected, message).set_source(err)
+            }
         };
 
         let code = r.status();
         if code.is_success() {
-            let result = r.json().await.map_err(make_error)?;
+            let Some(max_bytes) = max_bytes else {
+                let result = r.json().await.map_err(make_error)?;
+                return Ok(Response::Success(result));
+            };
+
+            let exceeded = || {
+                Error::new(
+                    ErrorKind::ResultBudgetExceeded,
+                    format!("response body exceeds {max_bytes} bytes"),
+                )
+                .with_context("max_result_bytes", max_bytes)
+                .set_permanent()
+            };
+            if r.content_length().is_some_and(|len| len > max_bytes as u64) {
+                return Err(exceeded());
+            }
+            let mut payload = Vec::new();
+            while let Some(chunk) = r.chunk().await.map_err(make_error)? {
+                if payload.len() + chunk.len() > max_bytes {
+                    return Err(exceeded());
+                }
+                payload.extend_from_slice(&chunk);
+            }
+            let result = serde_json::from_slice(&payload).map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "failed to make response").set_source(err)
+            })?;
             return Ok(Response::Success(result));
         }
 
@@ -66,6 +110,10 @@ pub struct ErrorStatus {
 }
 
 impl ErrorStatus {
+    pub fn new(code: StatusCode, message: String) -> Self {
+        Self { code, message }
+    }
+
     pub fn code(&self) -> StatusCode {
         self.code
     }
@@ -83,26 +131,170 @@ impl fmt::Display for ErrorStatus {
     }
 }
 
+/// HealthStatus reports whether the server is alive and ready to serve requests.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    /// Whether the server is up and responding.
+    #[serde(default = "default_true")]
+    pub live: bool,
+    /// Whether the server is ready to accept statements and ingestion.
+    #[serde(default = "default_true")]
+    pub ready: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[non_exhaustive]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "format")]
-pub(crate) enum IngestData {
+pub enum IngestData {
+    /// Rows in JSON lines, i.e., one JSON object per line.
     #[serde(rename = "json")]
     Json { rows: String },
+    /// Rows in a base64-encoded Arrow IPC stream, whose columns the transform statement refers
+    /// to by name.
+    #[cfg(feature = "arrow")]
+    #[serde(rename = "arrow")]
+    Arrow { rows: String },
 }
 
 impl IngestData {
+    /// Build JSON ingest data from rows, each of which must be a JSON object.
+    pub fn from_json_rows(rows: &[serde_json::Value]) -> Result<Self, Error> {
+        let mut lines = String::new();
+        for (i, row) in rows.iter().enumerate() {
+            if !row.is_object() {
+                return Err(Error::new(
+                    ErrorKind::Unexpected,
+                    format!("expected JSON object for ingest row {i}, got {row}"),
+                ));
+            }
+            if i > 0 {
+                lines.push('\n');
+            }
+            lines.push_str(&row.to_string());
+        }
+        Ok(Self::Json { rows: lines })
+    }
+
+    /// Build JSON ingest data from rows that serialize into JSON objects, e.g., structs or maps of
+    /// [`Value`](crate::Value)s.
+    pub fn from_rows<T: Serialize>(rows: &[T]) -> Result<Self, Error> {
+        let rows = rows
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    "failed to serialize ingest rows".to_string(),
+                )
+                .set_source(err)
+            })?;
+        Self::from_json_rows(&rows)
+    }
+
+    /// Build JSON ingest data from a JSON array of objects.
+    pub fn from_json_array(rows: &serde_json::Value) -> Result<Self, Error> {
+        match rows.as_array() {
+            Some(rows) => Self::from_json_rows(rows),
+            None => Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("expected JSON array of ingest rows, got {rows}"),
+            )),
+        }
+    }
+
+    /// Build Arrow ingest data from record batches, which must share a schema.
+    #[cfg(feature = "arrow")]
+    pub fn from_record_batches(batches: &[arrow_array::RecordBatch]) -> Result<Self, Error> {
+        let rows = crate::arrow::encode_batches(batches)?;
+        Ok(Self::Arrow { rows })
+    }
+
+    /// The same kind of data, without rows.
+    pub(crate) fn without_rows(&self) -> Self {
+        match self {
+            Self::Json { .. } => Self::Json {
+                rows: String::new(),
+            },
+            #[cfg(feature = "arrow")]
+            Self::Arrow { .. } => Self::Arrow {
+                rows: String::new(),
+            },
+        }
+    }
+
+    /// Split the rows into chunks whose encoded rows take at most `max_bytes` each in a JSON
+    /// request body, i.e., once escaped, except for single rows that are larger.
+    pub(crate) fn split(&self, max_bytes: usize) -> Result<Vec<Self>, Error> {
+        match self {
+            Self::Json { rows } => {
+                // The line break between rows is escaped as `\n`.
+                const SEPARATOR_LEN: usize = 2;
+
+                let mut chunks = vec![];
+                let mut chunk = String::new();
+                let mut chunk_len = 0;
+                for line in rows.lines() {
+                    // The quotes around the escaped line do not count.
+                    let line_len = json_len(line) - 2;
+                    if !chunk.is_empty() && chunk_len + SEPARATOR_LEN + line_len > max_bytes {
+                        chunks.push(Self::Json {
+                            rows: std::mem::take(&mut chunk),
+                        });
+                        chunk_len = 0;
+                    }
+                    if !chunk.is_empty() {
+                        chunk.push('\n');
+                        chunk_len += SEPARATOR_LEN;
+                    }
+                    chunk.push_str(line);
+                    chunk_len += line_len;
+                }
+                if !chunk.is_empty() {
+                    chunks.push(Self::Json { rows: chunk });
+                }
+                Ok(chunks)
+            }
+            #[cfg(feature = "arrow")]
+            Self::Arrow { rows } => Ok(crate::arrow::split_encoded_batches(rows, max_bytes)?
+                .into_iter()
+                .map(|rows| Self::Arrow { rows })
+                .collect()),
+        }
+    }
+
+    /// The encoded rows, as sent to the server.
+    pub fn rows(&self) -> &str {
+        match self {
+            Self::Json { rows } => rows,
+            #[cfg(feature = "arrow")]
+            Self::Arrow { rows } => rows,
+        }
+    }
+
     pub fn format(&self) -> &'static str {
         match self {
             Self::Json { .. } => "json",
+            #[cfg(feature = "arrow")]
+            Self::Arrow { .. } => "arrow",
         }
     }
 }
 
+/// How the server inserts ingested rows.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IngestType {
+    /// The rows are committed before the request returns.
     #[default]
     #[serde(rename = "committed")]
     Committed,
+    /// The rows are buffered by the server and committed later, trading visibility for
+    /// throughput.
     #[serde(rename = "buffered")]
     Buffered,
 }
@@ -114,6 +306,29 @@ pub struct IngestRequest {
     pub ty: IngestType,
     pub data: IngestData,
     pub statement: String,
+    /// Sent as the `Idempotency-Key` header rather than in the body.
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
+}
+
+/// The length of the value serialized into JSON, without allocating the serialized value.
+pub(crate) fn json_len<T: Serialize + ?Sized>(value: &T) -> usize {
+    struct Counter(usize);
+
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    serde_json::to_writer(&mut counter, value).expect("value must serialize into JSON");
+    counter.0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,15 +336,71 @@ pub struct IngestResult {
     pub num_rows_inserted: i64,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub(crate) enum ResultFormat {
+/// The wire format in which the server returns result sets.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResultFormat {
+    /// Rows of stringified cells in JSON.
+    #[default]
     #[serde(rename = "json")]
     Json,
+    /// A base64-encoded Arrow IPC stream, which preserves the cell types.
+    #[cfg(feature = "arrow")]
+    #[serde(rename = "arrow")]
+    Arrow,
+}
+
+/// A hint for the server on how to schedule a statement.
+///
+/// Priorities are not part of the confirmed server API yet; servers may ignore them.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Priority {
+    /// A latency-sensitive statement, e.g., a query issued by a user waiting for its result.
+    #[serde(rename = "interactive")]
+    Interactive,
+    /// A throughput-oriented statement, e.g., a step of an ETL job.
+    #[serde(rename = "batch")]
+    Batch,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatementRequestParams {
     pub format: ResultFormat,
+    /// Whether to return the partial result of a running statement, if the server supports it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_partial: Option<bool>,
+    /// The index of the first result row to return, if the server supports pagination.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+    /// The maximum number of result rows to return, if the server supports pagination.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// The maximum number of result rows the client accepts; an unconfirmed hint to the server.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_rows: Option<usize>,
+    /// The maximum size in bytes of the result the client accepts; an unconfirmed hint to the
+    /// server.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_result_bytes: Option<usize>,
+}
+
+impl StatementRequestParams {
+    pub fn new(format: ResultFormat) -> Self {
+        Self {
+            format,
+            allow_partial: None,
+            offset: None,
+            limit: None,
+            max_rows: None,
+            max_result_bytes: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,16 +415,34 @@ pub struct StatementRequest {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_parallelism: Option<usize>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// Labels attributing the statement to a workload, e.g., a team or a job.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
     #[serde(flatten)]
     pub params: StatementRequestParams,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementCancelRequest {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatementCancelResult {
     pub statement_id: Uuid,
     pub status: String,
     pub message: String,
     pub created_at: jiff::Timestamp,
+    /// The reason attached to the cancel request, if the server echoes it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -171,6 +460,102 @@ pub enum StatementStatus {
     Cancelled(StatementStatusCancelled),
 }
 
+/// The state of a statement, without the details carried by [`StatementStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementState {
+    Pending,
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+impl StatementState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatementState::Pending => "pending",
+            StatementState::Running => "running",
+            StatementState::Finished => "finished",
+            StatementState::Failed => "failed",
+            StatementState::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether the statement has finished, failed or been cancelled.
+    pub fn is_terminated(&self) -> bool {
+        matches!(
+            self,
+            StatementState::Finished | StatementState::Failed | StatementState::Cancelled
+        )
+    }
+}
+
+impl fmt::Display for StatementState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which statements [`Client::list_statements`](crate::Client::list_statements) returns.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StatementFilter {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<StatementState>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_after: Option<jiff::Timestamp>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+}
+
+impl StatementFilter {
+    /// Create a filter that matches all statements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only return statements in the given state.
+    pub fn with_state(mut self, state: StatementState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Only return statements created after the given time.
+    pub fn with_created_after(mut self, created_after: jiff::Timestamp) -> Self {
+        self.created_after = Some(created_after);
+        self
+    }
+
+    /// Return at most `limit` statements.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementList {
+    pub statements: Vec<StatementEntry>,
+}
+
+/// A statement known to the server, as returned by
+/// [`Client::list_statements`](crate::Client::list_statements).
+#[non_exhaustive]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementEntry {
+    pub statement_id: Uuid,
+    pub status: StatementState,
+    pub created_at: jiff::Timestamp,
+    pub progress: StatementEstimatedProgress,
+    /// The tags the statement was submitted with, if the server echoes them.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatementStatusPending {
@@ -185,6 +570,20 @@ pub struct StatementStatusRunning {
     pub statement_id: Uuid,
     pub created_at: jiff::Timestamp,
     pub progress: StatementEstimatedProgress,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result_set: Option<StatementResultSet>,
+}
+
+impl StatementStatusRunning {
+    /// The partial result produced so far, if requested with the unconfirmed `allow_partial` and
+    /// supported by the server.
+    pub fn partial_result_set(&self) -> Option<ResultSet> {
+        self.result_set
+            .clone()
+            .map(ResultSet::from_partial_statement_result_set)
+    }
 }
 
 #[non_exhaustive]
@@ -198,9 +597,18 @@ pub struct StatementStatusFinished {
 }
 
 impl StatementStatusFinished {
+    /// Return a copy of the result.
     pub fn result_set(&self) -> ResultSet {
         ResultSet::from_statement_result_set(self.result_set.clone())
     }
+
+    pub fn into_result_set(self) -> ResultSet {
+        ResultSet::from_statement_result_set(self.result_set)
+    }
+
+    pub(crate) fn num_rows(&self) -> usize {
+        self.result_set.metadata.num_rows
+    }
 }
 
 #[non_exhaustive]
@@ -210,6 +618,10 @@ pub struct StatementStatusFailed {
     pub created_at: jiff::Timestamp,
     pub progress: StatementEstimatedProgress,
     pub message: String,
+    /// The error code, if the server reports one; unconfirmed, as no server is known to send it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 #[non_exhaustive]
@@ -219,6 +631,10 @@ pub struct StatementStatusCancelled {
     pub created_at: jiff::Timestamp,
     pub progress: StatementEstimatedProgress,
     pub message: String,
+    /// The error code, if the server reports one; unconfirmed, as no server is known to send it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 impl StatementStatus {
@@ -252,6 +668,25 @@ impl StatementStatus {
         }
     }
 
+    pub fn state(&self) -> StatementState {
+        match self {
+            StatementStatus::Pending(..) => StatementState::Pending,
+            StatementStatus::Running(..) => StatementState::Running,
+            StatementStatus::Finished(..) => StatementState::Finished,
+            StatementStatus::Failed(..) => StatementState::Failed,
+            StatementStatus::Cancelled(..) => StatementState::Cancelled,
+        }
+    }
+
+    /// The message explaining why the statement failed or was cancelled.
+    pub fn failure_message(&self) -> Option<&str> {
+        match self {
+            StatementStatus::Failed(s) => Some(&s.message),
+            StatementStatus::Cancelled(s) => Some(&s.message),
+            _ => None,
+        }
+    }
+
     pub fn is_finished(&self) -> bool {
         matches!(self, StatementStatus::Finished(..))
     }
@@ -326,18 +761,38 @@ pub struct StatementResultSet {
 pub enum ResultSetData {
     #[serde(rename = "json")]
     Json { rows: Vec<Vec<Option<String>>> },
+    #[cfg(feature = "arrow")]
+    #[serde(rename = "arrow")]
+    Arrow { rows: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResultSetMetadata {
     pub fields: Vec<FieldMetadata>,
     pub num_rows: usize,
+    /// The number of rows of the whole result, when only a page of it is returned.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_rows: Option<usize>,
 }
 
+#[non_exhaustive]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldMetadata {
     pub name: String,
     pub data_type: DataType,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precision: Option<u32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullable: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub element_type: Option<DataType>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -349,6 +804,8 @@ pub enum DataType {
     UInt,
     #[serde(rename = "float")]
     Float,
+    #[serde(rename = "decimal")]
+    Decimal,
     #[serde(rename = "binary")]
     Binary,
     #[serde(rename = "string")]
@@ -377,6 +834,7 @@ impl FromStr for DataType {
             "int" => Ok(Self::Int),
             "uint" | "u_int" => Ok(Self::UInt),
             "float" => Ok(Self::Float),
+            "decimal" => Ok(Self::Decimal),
             "binary" => Ok(Self::Binary),
             "string" => Ok(Self::String),
             "boolean" => Ok(Self::Boolean),
@@ -393,3 +851,32 @@ impl FromStr for DataType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_ingest_data_from_json_rows() {
+        let data = IngestData::from_json_rows(&[json!({"a": 1}), json!({"b": "x\ny"})]).unwrap();
+        assert_eq!(data.rows(), "{\"a\":1}\n{\"b\":\"x\\ny\"}");
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            json!({"format": "json", "rows": data.rows()})
+        );
+    }
+
+    #[test]
+    fn test_ingest_data_rejects_non_objects() {
+        let err = IngestData::from_json_rows(&[json!({"a": 1}), json!([1, 2])]).unwrap_err();
+        assert!(err.to_string().contains("ingest row 1"));
+
+        let err = IngestData::from_json_array(&json!({"a": 1})).unwrap_err();
+        assert!(err.to_string().contains("expected JSON array"));
+
+        let data = IngestData::from_json_array(&json!([{"a": 1}])).unwrap();
+        assert_eq!(data.format(), "json");
+    }
+}
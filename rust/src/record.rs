@@ -0,0 +1,339 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Capture raw HTTP exchanges to a directory and replay them offline.
+//!
+//! Each exchange is written as `{seq:06}.json`. Sensitive headers are redacted before writing,
+//! and bodies are stored base64-encoded, so that binary and compressed bodies replay byte for
+//! byte. The directory is only touched once the first request is sent.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::OnceCell;
+
+use crate::Error;
+use crate::ErrorKind;
+
+const REDACTED: &str = "<redacted>";
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Exchange {
+    request: CapturedRequest,
+    response: CapturedResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CapturedRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    /// The base64-encoded body.
+    body: Option<String>,
+}
+
+impl CapturedRequest {
+    pub(crate) fn new(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            headers: capture_headers(request.headers()),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|body| STANDARD.encode(body)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CapturedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// The base64-encoded body.
+    body: String,
+}
+
+fn capture_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let name = name.as_str().to_string();
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+fn capture_error(message: String, err: impl Into<anyhow::Error>) -> Error {
+    Error::new(ErrorKind::Unexpected, message).set_source(err)
+}
+
+/// Recorder writes every request/response pair to a directory.
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    dir: PathBuf,
+    seq: AtomicUsize,
+    created: OnceCell<()>,
+}
+
+impl Recorder {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            seq: AtomicUsize::new(0),
+            created: OnceCell::new(),
+        }
+    }
+
+    /// Record the exchange and return an equivalent response, since reading the body consumes
+    /// the original one.
+    pub(crate) async fn record(
+        &self,
+        request: CapturedRequest,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response, Error> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|err| capture_error("failed to read response body".to_string(), err))?;
+
+        let exchange = Exchange {
+            request,
+            response: CapturedResponse {
+                status: status.as_u16(),
+                headers: capture_headers(&headers),
+                body: STANDARD.encode(&body),
+            },
+        };
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        let path = self.dir.join(format!("{seq:06}.json"));
+        let payload = serde_json::to_vec_pretty(&exchange)
+            .map_err(|err| capture_error("failed to serialize capture".to_string(), err))?;
+        self.created
+            .get_or_try_init(|| async {
+                tokio::fs::create_dir_all(&self.dir).await.map_err(|err| {
+                    capture_error(
+                        format!("failed to create capture directory {:?}", self.dir),
+                        err,
+                    )
+                })
+            })
+            .await?;
+        tokio::fs::write(&path, payload)
+            .await
+            .map_err(|err| capture_error(format!("failed to write capture {path:?}"), err))?;
+
+        let mut builder = http::Response::builder().status(status);
+        if let Some(response_headers) = builder.headers_mut() {
+            *response_headers = headers;
+        }
+        let response = builder
+            .body(body)
+            .map_err(|err| capture_error("failed to rebuild response".to_string(), err))?;
+        Ok(response.into())
+    }
+}
+
+/// Replayer serves responses from captures written by [`Recorder`], in order.
+#[derive(Debug)]
+pub(crate) struct Replayer {
+    dir: PathBuf,
+    exchanges: OnceCell<Mutex<VecDeque<(PathBuf, Exchange)>>>,
+}
+
+impl Replayer {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            exchanges: OnceCell::new(),
+        }
+    }
+
+    pub(crate) async fn replay(
+        &self,
+        request: &reqwest::Request,
+    ) -> Result<reqwest::Response, Error> {
+        let exchanges = self
+            .exchanges
+            .get_or_try_init(|| load_exchanges(&self.dir))
+            .await?;
+        let next = exchanges
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .pop_front();
+        let Some((path, exchange)) = next else {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                format!(
+                    "no capture left to replay {} {}",
+                    request.method(),
+                    request.url()
+                ),
+            ));
+        };
+
+        // Match on the path only, so that captures can be replayed against any endpoint.
+        let captured = &exchange.request;
+        let matched = reqwest::Url::parse(&captured.url).is_ok_and(|url| {
+            url.path() == request.url().path() && url.query() == request.url().query()
+        });
+        if captured.method != request.method().as_str() || !matched {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                format!(
+                    "replayed request {} {} does not match capture {} {}",
+                    request.method(),
+                    request.url(),
+                    captured.method,
+                    captured.url,
+                ),
+            )
+            .with_context("capture", path.display()));
+        }
+
+        let body = STANDARD
+            .decode(&exchange.response.body)
+            .map_err(|err| capture_error(format!("failed to decode capture {path:?}"), err))?;
+        let mut builder = http::Response::builder().status(exchange.response.status);
+        for (name, value) in &exchange.response.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .body(body)
+            .map_err(|err| capture_error(format!("failed to rebuild capture {path:?}"), err))?;
+        Ok(response.into())
+    }
+}
+
+/// Read the captures in the directory, in the order they were recorded.
+async fn load_exchanges(dir: &Path) -> Result<Mutex<VecDeque<(PathBuf, Exchange)>>, Error> {
+    let read_dir_error =
+        |err| capture_error(format!("failed to read capture directory {dir:?}"), err);
+    let mut entries = tokio::fs::read_dir(dir).await.map_err(read_dir_error)?;
+
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(read_dir_error)? {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut exchanges = VecDeque::with_capacity(paths.len());
+    for path in paths {
+        let payload = tokio::fs::read(&path)
+            .await
+            .map_err(|err| capture_error(format!("failed to read capture {path:?}"), err))?;
+        let exchange = serde_json::from_slice(&payload)
+            .map_err(|err| capture_error(format!("failed to parse capture {path:?}"), err))?;
+        exchanges.push_back((path, exchange));
+    }
+    Ok(Mutex::new(exchanges))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use super::*;
+    use crate::Client;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let dir = std::env::temp_dir().join(format!("scopedb-capture-{}", uuid::Uuid::now_v7()));
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("set-cookie", "session=secret")
+                    .set_body_json(testing::finished(testing::result_set(
+                        &[("v", "int")],
+                        json!([["42"]]),
+                    ))),
+            )
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).record(&dir).build().unwrap();
+        let rows = client
+            .statement("SELECT 42".to_string())
+            .execute()
+            .await
+            .unwrap()
+            .into_values()
+            .unwrap();
+        assert_eq!(format!("{rows:?}"), "[[42]]");
+
+        let capture = std::fs::read_to_string(dir.join("000000.json")).unwrap();
+        assert!(!capture.contains("secret"));
+        let capture: serde_json::Value = serde_json::from_str(&capture).unwrap();
+        let body = STANDARD
+            .decode(capture["request"]["body"].as_str().unwrap())
+            .unwrap();
+        assert!(String::from_utf8(body).unwrap().contains("SELECT 42"));
+
+        let uri = server.uri();
+        drop(server);
+        let client = Client::builder(uri).replay(&dir).build().unwrap();
+        let rows = client
+            .statement("SELECT 42".to_string())
+            .execute()
+            .await
+            .unwrap()
+            .into_values()
+            .unwrap();
+        assert_eq!(format!("{rows:?}"), "[[42]]");
+
+        let err = client
+            .statement("SELECT 42".to_string())
+            .execute()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no capture left"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
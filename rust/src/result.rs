@@ -12,30 +12,55 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::str::FromStr;
+use std::sync::Arc;
 
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Serialize;
+use serde::Serializer;
+use serde::de::DeserializeOwned;
+
+use crate::ColumnIndex;
 use crate::DataType;
 use crate::Error;
 use crate::ErrorKind;
+use crate::FromRow;
+use crate::FromValue;
+use crate::RowStream;
 use crate::protocol::ResultSetData;
 use crate::protocol::StatementResultSet;
 
+/// The fields of a result, cheap to clone.
 #[derive(Debug, Clone)]
 pub struct Schema {
-    pub(crate) fields: Vec<FieldSchema>,
+    pub(crate) fields: Arc<[FieldSchema]>,
 }
 
 impl Schema {
     pub fn fields(&self) -> &[FieldSchema] {
         &self.fields
     }
+
+    /// The position of the first field with the given name.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name == name)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct FieldSchema {
     pub(crate) name: String,
     pub(crate) data_type: DataType,
+    pub(crate) precision: Option<u32>,
+    pub(crate) scale: Option<i32>,
+    pub(crate) nullable: Option<bool>,
+    pub(crate) element_type: Option<DataType>,
 }
 
 impl FieldSchema {
@@ -46,6 +71,30 @@ impl FieldSchema {
     pub fn data_type(&self) -> DataType {
         self.data_type
     }
+
+    /// The precision of this field, if reported by the server: the number of digits for
+    /// numeric fields, or of fractional-second digits for timestamp and interval fields.
+    pub fn precision(&self) -> Option<u32> {
+        self.precision
+    }
+
+    /// The numeric scale of this field, if reported by the server.
+    pub fn scale(&self) -> Option<i32> {
+        self.scale
+    }
+
+    /// Whether this field may hold nulls, if reported by the server.
+    pub fn is_nullable(&self) -> Option<bool> {
+        self.nullable
+    }
+
+    /// The type of the elements of an array field, or of the entry values of an object field, if
+    /// reported by the server.
+    ///
+    /// Pass it to [`Value::elements_of`] or [`Value::entries_of`] to decode nested values.
+    pub fn element_type(&self) -> Option<DataType> {
+        self.element_type
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -53,13 +102,27 @@ pub struct ResultSet {
     schema: Schema,
     num_rows: usize,
     data: ResultSetData,
+    partial: bool,
+    total_rows: Option<usize>,
 }
 
 impl ResultSet {
+    /// Whether this is a partial result of a statement that is still running.
+    ///
+    /// Partial results may be approximate and are refined as the statement progresses.
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
     pub fn num_rows(&self) -> usize {
         self.num_rows
     }
 
+    /// The number of rows of the whole result, if this is a page of it and the total is known.
+    pub fn total_rows(&self) -> Option<usize> {
+        self.total_rows
+    }
+
     pub fn schema(&self) -> &Schema {
         &self.schema
     }
@@ -67,82 +130,208 @@ impl ResultSet {
     pub fn json_rows(&self) -> Option<&[Vec<Option<String>>]> {
         match &self.data {
             ResultSetData::Json { rows } => Some(rows),
+            #[cfg(feature = "arrow")]
+            ResultSetData::Arrow { .. } => None,
         }
     }
 
     pub fn into_values(self) -> Result<Vec<Vec<Value>>, Error> {
+        let (values, _) = self.into_values_with(ParseMode::Strict)?;
+        Ok(values)
+    }
+
+    /// Convert the result set into values, handling unparseable cells according to `mode`.
+    ///
+    /// Returns the values along with a warning for each cell that was kept as its raw string.
+    /// Cells of Arrow result sets are typed, so they are always converted strictly.
+    pub fn into_values_with(
+        self,
+        mode: ParseMode,
+    ) -> Result<(Vec<Vec<Value>>, Vec<ParseWarning>), Error> {
         let rows = match self.data {
             ResultSetData::Json { rows } => rows,
+            #[cfg(feature = "arrow")]
+            ResultSetData::Arrow { rows } => {
+                let batches = crate::arrow::decode_batches(&rows)?;
+                let values = crate::arrow::batches_to_values(&self.schema, &batches)?;
+                check_num_rows(values.len(), self.num_rows)?;
+                return Ok((values, vec![]));
+            }
         };
 
         let num_rows = self.num_rows;
-        let num_fields = self.schema.fields.len();
-        assert_eq!(rows.len(), num_rows);
+        check_num_rows(rows.len(), num_rows)?;
 
         let mut values = Vec::with_capacity(num_rows);
-        for row in rows {
-            assert_eq!(row.len(), num_fields);
-
-            let mut value_row = Vec::with_capacity(num_fields);
-            for (i, cell) in row.into_iter().enumerate() {
-                let Some(value) = cell else {
-                    value_row.push(Value::Null);
-                    continue;
-                };
-
-                let value = match self.schema.fields[i].data_type() {
-                    DataType::Int => Value::Int(i64::from_str(&value).map_err(|err| {
-                        Error::new(
-                            ErrorKind::Unexpected,
-                            format!("failed to parse int value: {err}"),
-                        )
-                    })?),
-                    DataType::UInt => Value::UInt(u64::from_str(&value).map_err(|err| {
-                        Error::new(
-                            ErrorKind::Unexpected,
-                            format!("failed to parse uint value: {err}"),
-                        )
-                    })?),
-                    DataType::Float => Value::Float(f64::from_str(&value).map_err(|err| {
-                        Error::new(
-                            ErrorKind::Unexpected,
-                            format!("failed to parse float value: {err}"),
-                        )
-                    })?),
-                    DataType::Timestamp => {
-                        Value::Timestamp(jiff::Timestamp::from_str(&value).map_err(|err| {
-                            Error::new(
-                                ErrorKind::Unexpected,
-                                format!("failed to parse timestamp value: {err}"),
-                            )
-                        })?)
-                    }
-                    DataType::Interval => {
-                        Value::Interval(jiff::SignedDuration::from_str(&value).map_err(|err| {
-                            Error::new(
-                                ErrorKind::Unexpected,
-                                format!("failed to parse interval value: {err}"),
-                            )
-                        })?)
-                    }
-                    DataType::Boolean => Value::Boolean(bool::from_str(&value).map_err(|err| {
-                        Error::new(
-                            ErrorKind::Unexpected,
-                            format!("failed to parse boolean value: {err}"),
-                        )
-                    })?),
-                    DataType::String => Value::String(value),
-                    DataType::Binary => Value::Binary(value),
-                    DataType::Array => Value::Array(value),
-                    DataType::Object => Value::Object(value),
-                    DataType::Any => Value::Any(value),
-                    DataType::Null => unreachable!("null values must be None in rows"),
-                };
-                value_row.push(value);
+        let mut warnings = vec![];
+        for (i, row) in rows.into_iter().enumerate() {
+            values.push(parse_row_with(&self.schema, row, mode, i, &mut warnings)?);
+        }
+        Ok((values, warnings))
+    }
+
+    /// Convert a single column, by position or by name, e.g. `column::<Option<i64>>("count")`.
+    ///
+    /// Only the cells of that column are parsed, so reading a few columns of a wide result is
+    /// cheaper than converting every row.
+    pub fn column<T: FromValue>(&self, index: impl ColumnIndex) -> Result<Vec<T>, Error> {
+        let i = index.column_index(&self.schema)?;
+        let field = &self.schema.fields[i];
+        let values = match &self.data {
+            ResultSetData::Json { rows } => {
+                check_num_rows(rows.len(), self.num_rows)?;
+                rows.iter()
+                    .map(|row| {
+                        check_row_len(&self.schema, row.len())?;
+                        match &row[i] {
+                            None => Ok(Value::Null),
+                            Some(cell) => parse_cell(field.data_type, cell.clone())
+                                .map_err(|err| Error::new(ErrorKind::Unexpected, err.message)),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+            #[cfg(feature = "arrow")]
+            ResultSetData::Arrow { rows } => {
+                let batches = crate::arrow::decode_batches(rows)?;
+                let num_rows = batches.iter().map(|batch| batch.num_rows()).sum();
+                check_num_rows(num_rows, self.num_rows)?;
+                crate::arrow::batches_column(&self.schema, &batches, i)?
+            }
+        };
+
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(row, value)| {
+                T::from_value(value).map_err(|err| {
+                    err.with_context("column", &field.name)
+                        .with_context("row", row)
+                })
+            })
+            .collect()
+    }
+
+    /// Convert the result set into Arrow record batches.
+    ///
+    /// Arrow result sets are returned as decoded from the server. JSON result sets are converted
+    /// into a single batch, where timestamps map to UTC microsecond timestamps, intervals to
+    /// month-day-nano intervals and binary values to bytes, while arrays, objects and any values
+    /// are kept as JSON strings.
+    #[cfg(feature = "arrow")]
+    pub fn into_record_batches(self) -> Result<Vec<arrow_array::RecordBatch>, Error> {
+        match self.data {
+            ResultSetData::Arrow { rows } => {
+                let batches = crate::arrow::decode_batches(&rows)?;
+                let num_rows = batches.iter().map(|batch| batch.num_rows()).sum();
+                check_num_rows(num_rows, self.num_rows)?;
+                Ok(batches)
+            }
+            ResultSetData::Json { .. } => {
+                let schema = self.schema.clone();
+                let values = self.into_values()?;
+                Ok(vec![crate::arrow::values_to_batch(&schema, &values)?])
             }
-            values.push(value_row);
         }
-        Ok(values)
+    }
+
+    /// Convert the result set into a Polars data frame.
+    ///
+    /// Timestamps map to microsecond datetimes in UTC and intervals to microsecond durations, with
+    /// days taken as 24 hours; intervals with months or years are rejected. Binary values are
+    /// decoded into bytes, while arrays, objects and any values are kept as JSON strings.
+    #[cfg(feature = "polars")]
+    pub fn into_dataframe(self) -> Result<polars::prelude::DataFrame, Error> {
+        let schema = self.schema.clone();
+        let values = self.into_values()?;
+        crate::dataframe::values_to_dataframe(&schema, &values)
+    }
+
+    /// Convert the result set into values lazily, one row at a time.
+    ///
+    /// Unlike [`into_values`](Self::into_values), the converted rows are not all held in memory
+    /// at once. Arrow result sets are converted one record batch at a time.
+    pub fn into_rows(self) -> Rows {
+        let inner = match self.data {
+            ResultSetData::Json { rows } => match check_num_rows(rows.len(), self.num_rows) {
+                Ok(()) => RowsInner::Json(rows.into_iter()),
+                Err(err) => RowsInner::Failed(Some(err)),
+            },
+            #[cfg(feature = "arrow")]
+            ResultSetData::Arrow { rows } => arrow_rows(&rows, self.num_rows),
+        };
+        Rows {
+            schema: self.schema,
+            inner,
+        }
+    }
+
+    /// Convert the rows into values lazily, one row at a time, without copying the result set.
+    pub(crate) fn rows(&self) -> Box<dyn Iterator<Item = Result<Vec<Value>, Error>> + '_> {
+        match &self.data {
+            ResultSetData::Json { rows } => match check_num_rows(rows.len(), self.num_rows) {
+                Ok(()) => Box::new(rows.iter().map(|row| parse_row(&self.schema, row.clone()))),
+                Err(err) => Box::new(std::iter::once(Err(err))),
+            },
+            #[cfg(feature = "arrow")]
+            ResultSetData::Arrow { rows } => Box::new(Rows {
+                schema: self.schema.clone(),
+                inner: arrow_rows(rows, self.num_rows),
+            }),
+        }
+    }
+
+    /// Convert the result set into a [`RowStream`] of values, for code that consumes rows
+    /// asynchronously.
+    pub fn into_stream(self) -> RowStream {
+        RowStream::from_result_set(self)
+    }
+
+    /// Convert the result set into typed rows, e.g. `into_typed::<(i64, String, bool)>()`.
+    pub fn into_typed<T: FromRow>(self) -> Result<Vec<T>, Error> {
+        let schema = self.schema.clone();
+        T::from_rows(&schema, self.into_values()?)
+    }
+
+    /// Convert each row into a JSON object keyed by field name, with values in the form of
+    /// [`Value::to_json`].
+    ///
+    /// If several fields share a name, the last one wins.
+    pub fn into_json_rows(self) -> Result<Vec<serde_json::Value>, Error> {
+        let schema = self.schema.clone();
+        self.into_rows()
+            .map(|row| {
+                let object = schema
+                    .fields
+                    .iter()
+                    .zip(row?)
+                    .map(|(field, value)| (field.name.clone(), value.into()))
+                    .collect();
+                Ok(serde_json::Value::Object(object))
+            })
+            .collect()
+    }
+
+    /// Deserialize each row into `T` with serde.
+    ///
+    /// A row is presented to `T` as a map from field names to the JSON form of its values, so a
+    /// struct deriving `Deserialize` picks its fields by column name. Nested arrays and objects
+    /// are deserialized as JSON, and timestamps and intervals from their string form.
+    pub fn deserialize<T: DeserializeOwned>(self) -> Result<Vec<T>, Error> {
+        self.into_json_rows()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, object)| {
+                serde_json::from_value(object).map_err(|err| {
+                    Error::new(
+                        ErrorKind::Unexpected,
+                        format!("failed to deserialize row: {err}"),
+                    )
+                    .set_source(err)
+                    .with_context("row", i)
+                })
+            })
+            .collect()
     }
 
     pub(crate) fn from_statement_result_set(result_set: StatementResultSet) -> ResultSet {
@@ -156,14 +345,393 @@ impl ResultSet {
                     .map(|field| FieldSchema {
                         name: field.name,
                         data_type: field.data_type,
+                        precision: field.precision,
+                        scale: field.scale,
+                        nullable: field.nullable,
+                        element_type: field.element_type,
                     })
                     .collect(),
             },
             data: result_set.data,
+            partial: false,
+            total_rows: result_set.metadata.total_rows,
+        }
+    }
+
+    /// Keep only the given page of rows if the server returned more rows than the page holds,
+    /// i.e., ignored the page and returned the whole result.
+    ///
+    /// A result that reports its total, or fits in the page, is taken as the page itself, since
+    /// a server may honor the page without reporting the total.
+    pub(crate) fn into_page(self, offset: usize, limit: usize) -> ResultSet {
+        if self.total_rows.is_some() || self.num_rows <= limit {
+            return self;
+        }
+        let rows = match self.data {
+            ResultSetData::Json { rows } => rows,
+            // Pages are only requested in JSON.
+            #[cfg(feature = "arrow")]
+            ResultSetData::Arrow { .. } => return self,
+        };
+        let total_rows = rows.len();
+        let rows = rows
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect::<Vec<_>>();
+        ResultSet {
+            num_rows: rows.len(),
+            data: ResultSetData::Json { rows },
+            total_rows: Some(total_rows),
+            ..self
+        }
+    }
+
+    pub(crate) fn from_partial_statement_result_set(result_set: StatementResultSet) -> ResultSet {
+        ResultSet {
+            partial: true,
+            ..Self::from_statement_result_set(result_set)
+        }
+    }
+}
+
+/// An iterator over the rows of a result set, converting each into values as it goes.
+///
+/// Created by [`ResultSet::into_rows`].
+#[derive(Debug)]
+pub struct Rows {
+    schema: Schema,
+    inner: RowsInner,
+}
+
+#[derive(Debug)]
+enum RowsInner {
+    Json(std::vec::IntoIter<Vec<Option<String>>>),
+    #[cfg(feature = "arrow")]
+    Arrow {
+        batches: std::vec::IntoIter<arrow_array::RecordBatch>,
+        current: std::vec::IntoIter<Vec<Value>>,
+        row_size: usize,
+    },
+    Failed(Option<Error>),
+}
+
+impl Rows {
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Return the next row along with its size in bytes.
+    ///
+    /// The size of a JSON row is the length of its cells; the size of an Arrow row is
+    /// estimated from the memory of its record batch.
+    pub(crate) fn next_sized(&mut self) -> Option<Result<(Vec<Value>, usize), Error>> {
+        match &mut self.inner {
+            RowsInner::Json(rows) => {
+                let row = rows.next()?;
+                let size = row.iter().flatten().map(String::len).sum();
+                Some(parse_row(&self.schema, row).map(|row| (row, size)))
+            }
+            #[cfg(feature = "arrow")]
+            RowsInner::Arrow {
+                batches,
+                current,
+                row_size,
+            } => loop {
+                if let Some(row) = current.next() {
+                    return Some(Ok((row, *row_size)));
+                }
+                let batch = batches.next()?;
+                *row_size = batch.get_array_memory_size() / batch.num_rows().max(1);
+                match crate::arrow::batches_to_values(&self.schema, &[batch]) {
+                    Ok(rows) => *current = rows.into_iter(),
+                    Err(err) => return Some(Err(err)),
+                }
+            },
+            RowsInner::Failed(err) => err.take().map(Err),
         }
     }
 }
 
+impl Iterator for Rows {
+    type Item = Result<Vec<Value>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_sized().map(|row| row.map(|(row, _)| row))
+    }
+}
+
+#[cfg(feature = "arrow")]
+fn arrow_rows(rows: &str, num_rows: usize) -> RowsInner {
+    let batches = crate::arrow::decode_batches(rows).and_then(|batches| {
+        check_num_rows(batches.iter().map(|batch| batch.num_rows()).sum(), num_rows)?;
+        Ok(batches)
+    });
+    match batches {
+        Ok(batches) => RowsInner::Arrow {
+            batches: batches.into_iter(),
+            current: vec![].into_iter(),
+            row_size: 0,
+        },
+        Err(err) => RowsInner::Failed(Some(err)),
+    }
+}
+
+fn check_num_rows(actual: usize, num_rows: usize) -> Result<(), Error> {
+    if actual != num_rows {
+        return Err(Error::new(
+            ErrorKind::Unexpected,
+            format!("result set has {actual} rows but its metadata declares {num_rows}"),
+        ));
+    }
+    Ok(())
+}
+
+/// How to handle cells that cannot be parsed into their declared type.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail the whole conversion.
+    #[default]
+    Strict,
+    /// Keep the raw string of the cell as [`Value::Any`] and record a [`ParseWarning`].
+    Lenient,
+}
+
+/// A cell that was kept as its raw string by [`ParseMode::Lenient`].
+#[derive(Debug, Clone)]
+pub struct ParseWarning {
+    row: usize,
+    column: usize,
+    message: String,
+}
+
+impl ParseWarning {
+    /// The index of the row of the cell.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// The index of the column of the cell.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Why the cell could not be parsed.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "row {} column {}: {}",
+            self.row, self.column, self.message
+        )
+    }
+}
+
+/// Convert an unparsed row into values according to the schema.
+fn parse_row(schema: &Schema, row: Vec<Option<String>>) -> Result<Vec<Value>, Error> {
+    parse_row_with(schema, row, ParseMode::Strict, 0, &mut vec![])
+}
+
+fn check_row_len(schema: &Schema, len: usize) -> Result<(), Error> {
+    // Cells are matched to fields by position, since the server does not tag cells with their
+    // fields. A row of the wrong length means the two are out of alignment.
+    let num_fields = schema.fields.len();
+    if len != num_fields {
+        let names = schema
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect::<Vec<_>>();
+        return Err(Error::new(
+            ErrorKind::Unexpected,
+            format!("row has {len} cells but the schema declares {num_fields} fields {names:?}"),
+        ));
+    }
+    Ok(())
+}
+
+fn parse_row_with(
+    schema: &Schema,
+    row: Vec<Option<String>>,
+    mode: ParseMode,
+    row_index: usize,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<Value>, Error> {
+    check_row_len(schema, row.len())?;
+
+    let num_fields = schema.fields.len();
+    let mut value_row = Vec::with_capacity(num_fields);
+    for (i, cell) in row.into_iter().enumerate() {
+        let Some(value) = cell else {
+            value_row.push(Value::Null);
+            continue;
+        };
+
+        let value = match (parse_cell(schema.fields[i].data_type(), value), mode) {
+            (Ok(value), _) => value,
+            (Err(err), ParseMode::Strict) => {
+                return Err(Error::new(ErrorKind::Unexpected, err.message));
+            }
+            (Err(err), ParseMode::Lenient) => {
+                warnings.push(ParseWarning {
+                    row: row_index,
+                    column: i,
+                    message: err.message,
+                });
+                Value::Any(err.raw)
+            }
+        };
+        value_row.push(value);
+    }
+    Ok(value_row)
+}
+
+/// A cell that failed to parse, with its raw value and the reason.
+pub(crate) struct CellError {
+    pub(crate) raw: String,
+    pub(crate) message: String,
+}
+
+pub(crate) fn parse_cell(data_type: DataType, value: String) -> Result<Value, CellError> {
+    let parsed = match data_type {
+        DataType::Int => i64::from_str(&value)
+            .map(Value::Int)
+            .map_err(|err| format!("failed to parse int value: {err}")),
+        DataType::UInt => u64::from_str(&value)
+            .map(Value::UInt)
+            .map_err(|err| format!("failed to parse uint value: {err}")),
+        // `f64::from_str` also accepts the NaN and infinity spellings, e.g., `NaN` and `-inf`.
+        DataType::Float => f64::from_str(&value)
+            .map(Value::Float)
+            .map_err(|err| format!("failed to parse float value: {err}")),
+        DataType::Decimal => rust_decimal::Decimal::from_str_exact(&value)
+            .or_else(|_| rust_decimal::Decimal::from_scientific(&value))
+            .map(Value::Decimal)
+            .map_err(|err| format!("failed to parse decimal value: {err}")),
+        DataType::Timestamp => jiff::Timestamp::from_str(&value)
+            .map(Value::Timestamp)
+            .map_err(|err| format!("failed to parse timestamp value: {err}")),
+        DataType::Interval => jiff::Span::from_str(&value)
+            .map(Value::Interval)
+            .map_err(|err| format!("failed to parse interval value: {err}")),
+        DataType::Boolean => bool::from_str(&value)
+            .map(Value::Boolean)
+            .map_err(|err| format!("failed to parse boolean value: {err}")),
+        DataType::String => return Ok(Value::String(value)),
+        DataType::Binary => return Ok(Value::Binary(value)),
+        DataType::Array => return Ok(Value::Array(value)),
+        DataType::Object => return Ok(Value::Object(value)),
+        DataType::Any => return Ok(Value::Any(value)),
+        DataType::Null => Err(format!("expected null cell for null field, got {value:?}")),
+    };
+    parsed.map_err(|message| CellError {
+        raw: value,
+        message,
+    })
+}
+
+/// Collect the cells of a column, converting non-null values with `convert`.
+#[cfg(any(feature = "arrow", feature = "polars"))]
+pub(crate) fn column_cells<'a, T>(
+    rows: &'a [Vec<Value>],
+    index: usize,
+    convert: impl Fn(&'a Value) -> Option<T>,
+) -> Result<Vec<Option<T>>, Error> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| match row.get(index) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => convert(value).map(Some).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    format!("cannot convert {value:?} to the column type"),
+                )
+                .with_context("row", i)
+            }),
+        })
+        .collect()
+}
+
+/// Convert a nested JSON value into a value of the given type, as if it were a result cell.
+fn json_to_value(json: serde_json::Value, data_type: DataType) -> Result<Value, Error> {
+    let cell = match json {
+        serde_json::Value::Null => return Ok(Value::Null),
+        serde_json::Value::String(v) => v,
+        json => json.to_string(),
+    };
+    parse_cell(data_type, cell).map_err(|err| Error::new(ErrorKind::Unexpected, err.message))
+}
+
+/// The largest precision of a decimal column converted to Arrow or Polars, whose decimals hold
+/// 128-bit mantissas.
+#[cfg(any(feature = "arrow", feature = "polars"))]
+const DECIMAL_MAX_PRECISION: u32 = 38;
+
+/// Convert the cells of a decimal column to mantissas sharing a single precision and scale.
+///
+/// The precision and scale are the ones reported for the field if any, or else the largest
+/// supported precision and the largest scale among the cells. Fails if a cell cannot be
+/// represented exactly with them.
+#[cfg(any(feature = "arrow", feature = "polars"))]
+pub(crate) fn decimal_mantissas(
+    field: &FieldSchema,
+    cells: Vec<Option<rust_decimal::Decimal>>,
+) -> Result<(Vec<Option<i128>>, u32, u32), Error> {
+    let precision = field
+        .precision
+        .filter(|precision| (1..=DECIMAL_MAX_PRECISION).contains(precision))
+        .unwrap_or(DECIMAL_MAX_PRECISION);
+    let scale = field
+        .scale
+        .and_then(|scale| u32::try_from(scale).ok())
+        .filter(|scale| *scale <= precision)
+        .unwrap_or_else(|| cells.iter().flatten().map(|v| v.scale()).max().unwrap_or(0));
+    let limit = 10u128.pow(precision);
+    let mantissa = |v: rust_decimal::Decimal| {
+        scale
+            .checked_sub(v.scale())
+            .and_then(|shift| 10i128.checked_pow(shift))
+            .and_then(|factor| v.mantissa().checked_mul(factor))
+            .filter(|mantissa| mantissa.unsigned_abs() < limit)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    format!(
+                        "decimal value {v} does not fit precision {precision} and scale {scale}"
+                    ),
+                )
+                .with_context("field", &field.name)
+            })
+    };
+    let mantissas = cells
+        .into_iter()
+        .map(|cell| cell.map(mantissa).transpose())
+        .collect::<Result<_, _>>()?;
+    Ok((mantissas, precision, scale))
+}
+
+/// Convert an interval into an exact duration, taking days as 24 hours.
+///
+/// Fails for intervals with months or years, whose length depends on a date.
+pub(crate) fn interval_to_duration(span: &jiff::Span) -> Result<jiff::SignedDuration, jiff::Error> {
+    span.to_duration(jiff::SpanRelativeTo::days_are_24_hours())
+}
+
+/// Decode a binary cell from hex, or else from base64.
+pub(crate) fn decode_binary(value: &str) -> Result<Vec<u8>, String> {
+    if value.len() % 2 == 0 && value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return hex::decode(value).map_err(|err| format!("failed to decode hex value: {err}"));
+    }
+    STANDARD
+        .decode(value)
+        .map_err(|_| format!("binary value is neither hex nor base64: {value:?}"))
+}
+
 #[derive(Clone)]
 pub enum Value {
     /// Signed integer value.
@@ -172,15 +740,17 @@ pub enum Value {
     UInt(u64),
     /// Float value.
     Float(f64),
+    /// Exact decimal value, e.g., for monetary amounts.
+    Decimal(rust_decimal::Decimal),
     /// Timestamp value.
     Timestamp(jiff::Timestamp),
-    /// Interval value.
-    Interval(jiff::SignedDuration),
+    /// Interval value, keeping calendar units such as months and days.
+    Interval(jiff::Span),
     /// Boolean value.
     Boolean(bool),
     /// String value.
     String(String),
-    /// Binary data represented as a hex string.
+    /// Binary data represented as a hex string; see [`Value::to_bytes`].
     Binary(String),
     /// Array of values, represented in its string format.
     Array(String),
@@ -192,12 +762,359 @@ pub enum Value {
     Null,
 }
 
+impl Value {
+    /// Build an array value from its elements, e.g., for ingesting into an array column.
+    pub fn array_from(values: impl IntoIterator<Item = Value>) -> Value {
+        let values = values.into_iter().map(|value| value.to_json()).collect();
+        Value::Array(serde_json::Value::Array(values).to_string())
+    }
+
+    /// Build an object value from its entries, e.g., for ingesting into an object column.
+    pub fn object_from<K: Into<String>>(entries: impl IntoIterator<Item = (K, Value)>) -> Value {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.to_json()))
+            .collect();
+        Value::Object(serde_json::Value::Object(entries).to_string())
+    }
+
+    /// Convert a JSON value into a value, inferring its type.
+    ///
+    /// Integers become [`Value::Int`], or [`Value::UInt`] if too large, other numbers become
+    /// [`Value::Float`], and nested arrays and objects keep their string form.
+    pub fn from_json(json: serde_json::Value) -> Value {
+        match json {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(v) => Value::Boolean(v),
+            serde_json::Value::Number(v) => match (v.as_i64(), v.as_u64(), v.as_f64()) {
+                (Some(v), _, _) => Value::Int(v),
+                (None, Some(v), _) => Value::UInt(v),
+                (None, None, Some(v)) => Value::Float(v),
+                (None, None, None) => Value::Any(v.to_string()),
+            },
+            serde_json::Value::String(v) => Value::String(v),
+            v @ serde_json::Value::Array(_) => Value::Array(v.to_string()),
+            v @ serde_json::Value::Object(_) => Value::Object(v.to_string()),
+        }
+    }
+
+    /// Decode the elements of an array value, inferring their types as in
+    /// [`from_json`](Self::from_json).
+    pub fn elements(&self) -> Result<Vec<Value>, Error> {
+        Ok(self
+            .json_array()?
+            .into_iter()
+            .map(Value::from_json)
+            .collect())
+    }
+
+    /// Decode the elements of an array value as values of the given type, e.g., the
+    /// [element type](FieldSchema::element_type) reported for the field.
+    pub fn elements_of(&self, element_type: DataType) -> Result<Vec<Value>, Error> {
+        self.json_array()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, json)| {
+                json_to_value(json, element_type).map_err(|err| err.with_context("element", i))
+            })
+            .collect()
+    }
+
+    /// Decode the entries of an object value, inferring the types of their values as in
+    /// [`from_json`](Self::from_json).
+    pub fn entries(&self) -> Result<BTreeMap<String, Value>, Error> {
+        Ok(self
+            .json_object()?
+            .into_iter()
+            .map(|(key, json)| (key, Value::from_json(json)))
+            .collect())
+    }
+
+    /// Decode the entries of an object value, with their values of the given type, e.g., the
+    /// [element type](FieldSchema::element_type) reported for the field.
+    pub fn entries_of(&self, value_type: DataType) -> Result<BTreeMap<String, Value>, Error> {
+        self.json_object()?
+            .into_iter()
+            .map(|(key, json)| match json_to_value(json, value_type) {
+                Ok(value) => Ok((key, value)),
+                Err(err) => Err(err.with_context("key", key)),
+            })
+            .collect()
+    }
+
+    fn json_array(&self) -> Result<Vec<serde_json::Value>, Error> {
+        match self {
+            Value::Array(v) | Value::Any(v) => match serde_json::from_str(v) {
+                Ok(serde_json::Value::Array(elements)) => Ok(elements),
+                _ => Err(Error::new(
+                    ErrorKind::Unexpected,
+                    format!("expected JSON array, got {v}"),
+                )),
+            },
+            value => Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("expected array value, got {value:?}"),
+            )),
+        }
+    }
+
+    fn json_object(&self) -> Result<serde_json::Map<String, serde_json::Value>, Error> {
+        match self {
+            Value::Object(v) | Value::Any(v) => match serde_json::from_str(v) {
+                Ok(serde_json::Value::Object(entries)) => Ok(entries),
+                _ => Err(Error::new(
+                    ErrorKind::Unexpected,
+                    format!("expected JSON object, got {v}"),
+                )),
+            },
+            value => Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("expected object value, got {value:?}"),
+            )),
+        }
+    }
+
+    /// Decode a binary value into its bytes.
+    ///
+    /// Binary values are expected in hex, as returned by the server, but base64 is accepted too,
+    /// e.g., for values built by hand. A string that is valid in both is decoded as hex.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        match self {
+            Value::Binary(v) => {
+                decode_binary(v).map_err(|message| Error::new(ErrorKind::Unexpected, message))
+            }
+            value => Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("expected binary value, got {value:?}"),
+            )),
+        }
+    }
+
+    /// Convert the value into its JSON form.
+    ///
+    /// Arrays, objects and any values are parsed into nested JSON, so they can be traversed
+    /// directly. Decimals, timestamps and intervals become strings, as do floats that JSON cannot
+    /// represent, i.e., NaN and infinities.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Int(v) => (*v).into(),
+            Value::UInt(v) => (*v).into(),
+            // JSON has no representation for NaN and infinities.
+            Value::Float(v) => serde_json::Number::from_f64(*v)
+                .map_or_else(|| self.to_string().into(), serde_json::Value::Number),
+            // Decimals are kept as strings, since JSON numbers are commonly read as floats.
+            Value::Decimal(v) => v.to_string().into(),
+            Value::Timestamp(v) => v.to_string().into(),
+            Value::Interval(v) => v.to_string().into(),
+            Value::Boolean(v) => (*v).into(),
+            Value::String(v) | Value::Binary(v) => v.clone().into(),
+            Value::Array(v) | Value::Object(v) | Value::Any(v) => {
+                serde_json::from_str(v).unwrap_or_else(|_| v.clone().into())
+            }
+            Value::Null => serde_json::Value::Null,
+        }
+    }
+}
+
+impl From<Value> for serde_json::Value {
+    fn from(value: Value) -> Self {
+        value.to_json()
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(value.into())
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::UInt(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<rust_decimal::Decimal> for Value {
+    fn from(value: rust_decimal::Decimal) -> Self {
+        Value::Decimal(value)
+    }
+}
+
+impl From<jiff::Timestamp> for Value {
+    fn from(value: jiff::Timestamp) -> Self {
+        Value::Timestamp(value)
+    }
+}
+
+impl From<jiff::Span> for Value {
+    fn from(value: jiff::Span) -> Self {
+        Value::Interval(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Boolean(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+/// Converts bytes into a binary value, hex-encoded.
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Value::Binary(hex::encode(value))
+    }
+}
+
+/// Converts `None` into [`Value::Null`].
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+/// Values serialize to their JSON form as expected by ingestion, with nested arrays and objects
+/// emitted as JSON rather than strings.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+/// Values compare for sorting, deduplication and grouping on the client side.
+///
+/// - Values of different variants order by variant, in declaration order, so `Int(2)` is less than
+///   `UInt(1)`. Numbers of different variants are never equal.
+/// - Unlike in SQL, `Null` equals `Null`, and sorts after every other value.
+/// - Floats compare with [`f64::total_cmp`]: `NaN` equals itself and sorts after every other float,
+///   while `-0.0` sorts before and differs from `0.0`.
+/// - Decimals compare by their numeric value, so `1.0` equals `1.00`.
+/// - Intervals compare field by field from years down to nanoseconds, so one hour differs from
+///   sixty minutes.
+/// - Binary values, arrays, objects and any values compare by their string form.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::UInt(a), Value::UInt(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::Decimal(a), Value::Decimal(b)) => a.cmp(b),
+            (Value::Timestamp(a), Value::Timestamp(b)) => a.cmp(b),
+            (Value::Interval(a), Value::Interval(b)) => span_fields(a).cmp(&span_fields(b)),
+            (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b))
+            | (Value::Binary(a), Value::Binary(b))
+            | (Value::Array(a), Value::Array(b))
+            | (Value::Object(a), Value::Object(b))
+            | (Value::Any(a), Value::Any(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match self {
+            Value::Int(v) => v.hash(state),
+            Value::UInt(v) => v.hash(state),
+            Value::Float(v) => v.to_bits().hash(state),
+            Value::Decimal(v) => v.hash(state),
+            Value::Timestamp(v) => v.hash(state),
+            Value::Interval(v) => span_fields(v).hash(state),
+            Value::Boolean(v) => v.hash(state),
+            Value::String(v)
+            | Value::Binary(v)
+            | Value::Array(v)
+            | Value::Object(v)
+            | Value::Any(v) => v.hash(state),
+            Value::Null => {}
+        }
+    }
+}
+
+impl Value {
+    /// The position of the variant in the order of values of different variants.
+    fn rank(&self) -> u8 {
+        match self {
+            Value::Int(_) => 0,
+            Value::UInt(_) => 1,
+            Value::Float(_) => 2,
+            Value::Decimal(_) => 3,
+            Value::Timestamp(_) => 4,
+            Value::Interval(_) => 5,
+            Value::Boolean(_) => 6,
+            Value::String(_) => 7,
+            Value::Binary(_) => 8,
+            Value::Array(_) => 9,
+            Value::Object(_) => 10,
+            Value::Any(_) => 11,
+            Value::Null => 12,
+        }
+    }
+}
+
+/// The fields of a span, from the largest unit to the smallest, which are signed by the sign of
+/// the span.
+fn span_fields(span: &jiff::Span) -> (i16, i32, i32, i32, i32, i64, i64, i64, i64, i64) {
+    (
+        span.get_years(),
+        span.get_months(),
+        span.get_weeks(),
+        span.get_days(),
+        span.get_hours(),
+        span.get_minutes(),
+        span.get_seconds(),
+        span.get_milliseconds(),
+        span.get_microseconds(),
+        span.get_nanoseconds(),
+    )
+}
+
 impl fmt::Debug for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Int(v) => write!(f, "{v}"),
             Value::UInt(v) => write!(f, "{v}"),
             Value::Float(v) => write!(f, "{v:?}"),
+            Value::Decimal(v) => write!(f, "{v}"),
             Value::Timestamp(v) => format_timestamp(f, v),
             Value::Interval(v) => format_interval(f, v),
             Value::Boolean(v) => write!(f, "{v}"),
@@ -217,6 +1134,7 @@ impl fmt::Display for Value {
             Value::Int(v) => write!(f, "{v}"),
             Value::UInt(v) => write!(f, "{v}"),
             Value::Float(v) => write!(f, "{v:?}"),
+            Value::Decimal(v) => write!(f, "{v}"),
             Value::Timestamp(v) => format_timestamp(f, v),
             Value::Interval(v) => format_interval(f, v),
             Value::Boolean(v) => write!(f, "{v}"),
@@ -241,14 +1159,14 @@ fn format_timestamp(f: &mut fmt::Formatter<'_>, ts: &jiff::Timestamp) -> fmt::Re
         .map_err(|_| fmt::Error)
 }
 
-fn format_interval(f: &mut fmt::Formatter<'_>, d: &jiff::SignedDuration) -> fmt::Result {
+fn format_interval(f: &mut fmt::Formatter<'_>, span: &jiff::Span) -> fmt::Result {
     use jiff::fmt::StdFmtWrite;
     use jiff::fmt::friendly;
 
     friendly::SpanPrinter::new()
         .spacing(friendly::Spacing::None)
         .designator(friendly::Designator::Compact)
-        .print_duration(d, StdFmtWrite(f))
+        .print_span(span, StdFmtWrite(f))
         .map_err(|_| fmt::Error)
 }
 
@@ -272,3 +1190,438 @@ fn quote_string(f: &mut fmt::Formatter<'_>, s: &str, quote: char) -> fmt::Result
     }
     write!(f, "{quote}")
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::FromValue;
+    use crate::IngestData;
+
+    fn test_result_set(payload: serde_json::Value) -> ResultSet {
+        let result_set: StatementResultSet = serde_json::from_value(payload).unwrap();
+        ResultSet::from_statement_result_set(result_set)
+    }
+
+    #[test]
+    fn test_into_values_row_count_mismatch() {
+        let err = test_result_set(json!({
+            "metadata": {
+                "fields": [{"name": "v", "data_type": "int"}],
+                "num_rows": 3,
+            },
+            "format": "json",
+            "rows": [["1"], ["2"]],
+        }))
+        .into_values()
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("result set has 2 rows but its metadata declares 3")
+        );
+    }
+
+    #[test]
+    fn test_into_values_row_length_mismatch() {
+        let err = test_result_set(json!({
+            "metadata": {
+                "fields": [
+                    {"name": "a", "data_type": "int"},
+                    {"name": "b", "data_type": "string"},
+                ],
+                "num_rows": 2,
+            },
+            "format": "json",
+            "rows": [["1", "x"], ["2"]],
+        }))
+        .into_values()
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(r#"row has 1 cells but the schema declares 2 fields ["a", "b"]"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_into_rows() {
+        use futures::TryStreamExt;
+
+        let result_set = test_result_set(json!({
+            "metadata": {
+                "fields": [{"name": "v", "data_type": "int"}],
+                "num_rows": 3,
+            },
+            "format": "json",
+            "rows": [["1"], ["2"], ["x"]],
+        }));
+
+        let mut rows = result_set.clone().into_rows();
+        assert_eq!(format!("{:?}", rows.next().unwrap().unwrap()), "[1]");
+        assert_eq!(format!("{:?}", rows.next().unwrap().unwrap()), "[2]");
+        let err = rows.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("failed to parse int value"));
+        assert!(rows.next().is_none());
+
+        let mut stream = result_set.into_stream().with_max_rows(1);
+        assert_eq!(
+            format!("{:?}", stream.try_next().await.unwrap().unwrap()),
+            "[1]"
+        );
+        let err = stream.try_next().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ResultBudgetExceeded);
+    }
+
+    #[test]
+    fn test_into_values_parse_mode() {
+        let result_set = test_result_set(json!({
+            "metadata": {
+                "fields": [
+                    {"name": "a", "data_type": "int"},
+                    {"name": "b", "data_type": "boolean"},
+                ],
+                "num_rows": 2,
+            },
+            "format": "json",
+            "rows": [["1", "true"], ["two", "yes"]],
+        }));
+
+        let err = result_set.clone().into_values().unwrap_err();
+        assert!(err.to_string().contains("failed to parse int value"));
+
+        let (values, warnings) = result_set.into_values_with(ParseMode::Lenient).unwrap();
+        assert_eq!(format!("{values:?}"), "[[1, true], [two, yes]]");
+        assert!(matches!(&values[1][0], Value::Any(v) if v == "two"));
+        let warnings = warnings
+            .iter()
+            .map(|w| (w.row(), w.column()))
+            .collect::<Vec<_>>();
+        assert_eq!(warnings, [(1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn test_into_values_special_floats() {
+        let values = test_result_set(json!({
+            "metadata": {
+                "fields": [{"name": "f", "data_type": "float"}],
+                "num_rows": 6,
+            },
+            "format": "json",
+            "rows": [
+                ["NaN"],
+                ["Infinity"],
+                ["-Infinity"],
+                ["5e-324"],
+                ["-2.225073858507201e-308"],
+                ["1.5"],
+            ],
+        }))
+        .into_values()
+        .unwrap();
+        let floats = values
+            .into_iter()
+            .map(|row| match row[0] {
+                Value::Float(v) => v,
+                ref value => panic!("expected float value, got {value:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert!(floats[0].is_nan());
+        assert_eq!(floats[1], f64::INFINITY);
+        assert_eq!(floats[2], f64::NEG_INFINITY);
+        assert_eq!(floats[3], f64::from_bits(1));
+        assert!(floats[3].is_subnormal());
+        assert!(floats[4].is_subnormal() && floats[4].is_sign_negative());
+        assert_eq!(floats[5], 1.5);
+    }
+
+    #[test]
+    fn test_into_values_non_null_cell_for_null_field() {
+        let err = test_result_set(json!({
+            "metadata": {
+                "fields": [{"name": "n", "data_type": "null"}],
+                "num_rows": 1,
+            },
+            "format": "json",
+            "rows": [["x"]],
+        }))
+        .into_values()
+        .unwrap_err();
+        assert!(err.to_string().contains("expected null cell"));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Event {
+            id: i64,
+            name: Option<String>,
+            ts: jiff::Timestamp,
+            tags: Vec<String>,
+        }
+
+        let result_set = test_result_set(json!({
+            "metadata": {
+                "fields": [
+                    {"name": "id", "data_type": "int"},
+                    {"name": "ts", "data_type": "timestamp"},
+                    {"name": "name", "data_type": "string"},
+                    {"name": "tags", "data_type": "array"},
+                ],
+                "num_rows": 2,
+            },
+            "format": "json",
+            "rows": [
+                ["1", "2024-01-01T00:00:00Z", "alpha", r#"["a","b"]"#],
+                ["2", "2024-01-02T00:00:00Z", null, "[]"],
+            ],
+        }));
+        let events = result_set.clone().deserialize::<Event>().unwrap();
+        assert_eq!(
+            events,
+            [
+                Event {
+                    id: 1,
+                    name: Some("alpha".to_string()),
+                    ts: "2024-01-01T00:00:00Z".parse().unwrap(),
+                    tags: vec!["a".to_string(), "b".to_string()],
+                },
+                Event {
+                    id: 2,
+                    name: None,
+                    ts: "2024-01-02T00:00:00Z".parse().unwrap(),
+                    tags: vec![],
+                },
+            ]
+        );
+
+        assert_eq!(
+            result_set.clone().into_json_rows().unwrap()[1],
+            json!({"id": 2, "ts": "2024-01-02T00:00:00Z", "name": null, "tags": []})
+        );
+        assert_eq!(serde_json::Value::from(Value::Int(1)), json!(1));
+
+        let err = result_set.deserialize::<(i64, i64)>().unwrap_err();
+        assert!(
+            err.to_string().contains("failed to deserialize row"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_interval_keeps_calendar_units() {
+        let values = test_result_set(json!({
+            "metadata": {
+                "fields": [{"name": "v", "data_type": "interval"}],
+                "num_rows": 2,
+            },
+            "format": "json",
+            "rows": [["P1M2DT3H"], ["PT90M"]],
+        }))
+        .into_values()
+        .unwrap();
+        assert_eq!(format!("{values:?}"), "[[1mo2d3h], [90m]]");
+        assert_eq!(values[0][0].to_json(), json!("P1M2DT3H"));
+
+        let err = jiff::SignedDuration::from_value(values[0][0].clone()).unwrap_err();
+        assert!(err.to_string().contains("cannot convert interval"), "{err}");
+        let duration = jiff::SignedDuration::from_value(values[1][0].clone()).unwrap();
+        assert_eq!(duration, jiff::SignedDuration::from_mins(90));
+        let span = jiff::Span::from_value(Value::Interval("P2D".parse().unwrap())).unwrap();
+        assert_eq!(
+            jiff::SignedDuration::from_value(Value::Interval(span)).unwrap(),
+            jiff::SignedDuration::from_hours(48)
+        );
+    }
+
+    #[cfg(any(feature = "arrow", feature = "polars"))]
+    #[test]
+    fn test_decimal_mantissas() {
+        let field = |precision, scale| FieldSchema {
+            name: "amount".to_string(),
+            data_type: DataType::Decimal,
+            precision,
+            scale,
+            nullable: None,
+            element_type: None,
+        };
+        let cells = ["1.5", "-0.25", "1000"].map(|v| Some(v.parse().unwrap()));
+
+        let (mantissas, precision, scale) =
+            decimal_mantissas(&field(Some(10), Some(4)), [cells[0], None].to_vec()).unwrap();
+        assert_eq!(
+            (mantissas, precision, scale),
+            (vec![Some(15_000), None], 10, 4)
+        );
+        let (mantissas, precision, scale) =
+            decimal_mantissas(&field(None, None), cells.to_vec()).unwrap();
+        assert_eq!(
+            (mantissas, precision, scale),
+            (vec![Some(150), Some(-25), Some(100_000)], 38, 2)
+        );
+
+        // Rounding to a smaller scale or exceeding the precision fails rather than change a value.
+        let err = decimal_mantissas(&field(Some(10), Some(1)), cells.to_vec()).unwrap_err();
+        assert!(err.to_string().contains("-0.25 does not fit"), "{err}");
+        let err = decimal_mantissas(&field(Some(4), Some(2)), cells.to_vec()).unwrap_err();
+        assert!(err.to_string().contains("1000 does not fit"), "{err}");
+        let large = rust_decimal::Decimal::MAX;
+        let err = decimal_mantissas(&field(None, Some(28)), vec![Some(large)]).unwrap_err();
+        assert!(
+            err.to_string().contains("does not fit precision 38"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_decimal_round_trip() {
+        let values = test_result_set(json!({
+            "metadata": {
+                "fields": [{"name": "amount", "data_type": "decimal", "precision": 38, "scale": 2}],
+                "num_rows": 3,
+            },
+            "format": "json",
+            "rows": [["12345678901234567890.12"], ["-0.10"], ["1e3"]],
+        }))
+        .into_values()
+        .unwrap();
+        assert_eq!(
+            format!("{values:?}"),
+            "[[12345678901234567890.12], [-0.10], [1000]]"
+        );
+
+        let row = BTreeMap::from([("amount", values[0][0].clone())]);
+        let data = IngestData::from_rows(&[row]).unwrap();
+        assert_eq!(
+            data.rows().trim(),
+            r#"{"amount":"12345678901234567890.12"}"#
+        );
+
+        let decimal = rust_decimal::Decimal::from_value(values[1][0].clone()).unwrap();
+        assert_eq!(decimal, rust_decimal::Decimal::new(-10, 2));
+    }
+
+    #[test]
+    fn test_field_metadata() {
+        let result_set = test_result_set(json!({
+            "metadata": {
+                "fields": [
+                    {"name": "ts", "data_type": "timestamp", "precision": 6, "nullable": false},
+                    {"name": "v", "data_type": "int"},
+                ],
+                "num_rows": 0,
+            },
+            "format": "json",
+            "rows": [],
+        }));
+        let fields = result_set.schema().fields();
+        assert_eq!(fields[0].precision(), Some(6));
+        assert_eq!(fields[0].is_nullable(), Some(false));
+        assert_eq!(fields[1].precision(), None);
+        assert_eq!(fields[1].is_nullable(), None);
+    }
+
+    #[test]
+    fn test_value_ordering() {
+        use std::collections::HashSet;
+
+        let mut values = vec![
+            Value::Null,
+            Value::String("b".to_string()),
+            Value::Float(f64::NAN),
+            Value::UInt(1),
+            Value::Float(-0.0),
+            Value::Int(2),
+            Value::Float(0.0),
+            Value::String("a".to_string()),
+            Value::Int(-1),
+        ];
+        values.sort();
+        assert_eq!(
+            format!("{values:?}"),
+            "[-1, 2, 1, -0.0, 0.0, NaN, 'a', 'b', NULL]"
+        );
+
+        assert_eq!(Value::Null, Value::Null);
+        assert_eq!(Value::Float(f64::NAN), Value::Float(f64::NAN));
+        assert_ne!(Value::Int(1), Value::UInt(1));
+        assert_eq!(
+            Value::Decimal("1.0".parse().unwrap()),
+            Value::Decimal("1.00".parse().unwrap())
+        );
+        assert_ne!(
+            Value::Interval(jiff::Span::new().hours(1)),
+            Value::Interval(jiff::Span::new().minutes(60))
+        );
+        assert!(Value::Interval(jiff::Span::new().days(-1)) < Value::Interval(jiff::Span::new()));
+
+        let distinct = [
+            Value::Decimal("1.0".parse().unwrap()),
+            Value::Decimal("1.00".parse().unwrap()),
+            Value::Null,
+            Value::Null,
+            Value::Float(f64::NAN),
+            Value::Float(f64::NAN),
+            Value::Int(1),
+            Value::UInt(1),
+        ]
+        .into_iter()
+        .collect::<HashSet<_>>();
+        assert_eq!(distinct.len(), 5);
+    }
+
+    #[test]
+    fn test_binary_to_bytes() {
+        let bytes = |v: &str| Value::Binary(v.to_string()).to_bytes();
+        assert_eq!(bytes("BEef00").unwrap(), [0xbe, 0xef, 0x00]);
+        assert_eq!(bytes("").unwrap(), [0u8; 0]);
+        assert_eq!(bytes("vu8=").unwrap(), [0xbe, 0xef]);
+        assert!(bytes("not binary!").is_err());
+        assert!(Value::String("beef".to_string()).to_bytes().is_err());
+    }
+
+    #[test]
+    fn test_nested_values_round_trip() {
+        let array = Value::array_from([
+            Value::Int(1),
+            Value::Null,
+            Value::object_from([("a", Value::String("x".to_string()))]),
+        ]);
+        let object = Value::object_from([
+            ("tags", Value::array_from([Value::String("t".to_string())])),
+            ("n", Value::Float(1.5)),
+        ]);
+
+        let row = BTreeMap::from([("array", array.clone()), ("object", object.clone())]);
+        let data = IngestData::from_rows(&[row]).unwrap();
+        let rows = data.rows();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(rows).unwrap(),
+            json!({
+                "array": [1, null, {"a": "x"}],
+                "object": {"n": 1.5, "tags": ["t"]},
+            })
+        );
+
+        let result_set: StatementResultSet = serde_json::from_value(json!({
+            "metadata": {
+                "fields": [
+                    {"name": "array", "data_type": "array"},
+                    {"name": "object", "data_type": "object"},
+                ],
+                "num_rows": 1,
+            },
+            "format": "json",
+            "rows": [[array.to_string(), object.to_string()]],
+        }))
+        .unwrap();
+        let values = ResultSet::from_statement_result_set(result_set)
+            .into_values()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&values[0]).unwrap(),
+            json!([[1, null, {"a": "x"}], {"n": 1.5, "tags": ["t"]}])
+        );
+    }
+}
@@ -0,0 +1,414 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use jiff::Timestamp;
+use jiff::fmt::rfc2822::DateTimeParser;
+use reqwest::Method;
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+use serde::Deserialize;
+use tokio::time::sleep;
+use uuid::Uuid;
+
+use crate::BoxFuture;
+use crate::Error;
+use crate::Middleware;
+use crate::Next;
+
+/// How the client retries requests that fail temporarily.
+///
+/// A request is retried, up to `max_attempts` attempts in total, when it fails with a
+/// [temporary](Error::is_temporary) error, such as a connection failure or a client-side timeout,
+/// or when the server responds with `429 Too Many Requests` or a `5xx` status. Between attempts,
+/// the client backs off for a random duration between half and all of the current backoff, which
+/// starts at `initial_backoff` and doubles up to `max_backoff`. When a `429` or `503` response
+/// carries a `Retry-After` header, the client waits that long instead, or returns the response
+/// if the wait would exceed `max_backoff`. An error that persists through all attempts is marked
+/// [persistent](Error::is_persistent); a response is returned as is.
+///
+/// Set a policy with [`ClientBuilder::retry_policy`]. It runs ahead of all other middlewares, so
+/// that every attempt flows through them. A request that failed to connect never reached the
+/// server and is always retried. Otherwise, only requests that are safe to repeat are retried:
+/// fetching statements and other `GET` requests, cancelling statements, submitting statements
+/// with an id set by the caller with [`Statement::with_statement_id`], which the server
+/// deduplicates on, and ingesting data that the caller marked as repeatable with an
+/// [idempotency key](crate::IngestBuilder::idempotency_key). Submitting a statement without an
+/// id or ingesting data without a key is sent once, since the server may have acted on a request
+/// whose response was lost. Requests with a streaming body cannot be replayed and are sent once,
+/// too.
+///
+/// [`Statement::with_statement_id`]: crate::Statement::with_statement_id
+///
+/// [`ClientBuilder::retry_policy`]: crate::ClientBuilder::retry_policy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of attempts, including the first one; values below 1 are taken as 1.
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    pub fn initial_backoff(&self) -> Duration {
+        self.initial_backoff
+    }
+
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+
+    /// The backoff before the given retry, counted from 1, with jitter applied.
+    fn backoff(&self, retry: usize) -> Duration {
+        let exp = u32::try_from(retry - 1).unwrap_or(u32::MAX);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2u32.saturating_pow(exp))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        backoff / 2 + backoff.mul_f64(fastrand::f64() / 2.0)
+    }
+
+    /// The delay before retrying after the given result, or `None` if it is not to be retried.
+    fn delay(&self, result: &Result<reqwest::Response, Error>, retry: usize) -> Option<Duration> {
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                if status != StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                    return None;
+                }
+                match retry_after(response) {
+                    Some(delay) => (delay <= self.max_backoff).then_some(delay),
+                    None => Some(self.backoff(retry)),
+                }
+            }
+            Err(err) => err.is_temporary().then(|| self.backoff(retry)),
+        }
+    }
+}
+
+/// The header that marks an ingest request as safe to retry.
+pub(crate) const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// Whether sending the request again has no effect beyond that of the first attempt.
+fn is_idempotent(request: &reqwest::Request) -> bool {
+    match *request.method() {
+        Method::GET => true,
+        Method::POST => {
+            let path = request.url().path();
+            path.ends_with("/cancel")
+                || request.headers().contains_key(IDEMPOTENCY_KEY)
+                || (path.ends_with("/v1/statements") && has_statement_id(request))
+        }
+        _ => false,
+    }
+}
+
+/// Whether the statement submit request carries a statement id.
+fn has_statement_id(request: &reqwest::Request) -> bool {
+    #[derive(Deserialize)]
+    struct Submit {
+        statement_id: Option<Uuid>,
+    }
+
+    request
+        .body()
+        .and_then(reqwest::Body::as_bytes)
+        .and_then(|body| serde_json::from_slice::<Submit>(body).ok())
+        .is_some_and(|submit| submit.statement_id.is_some())
+}
+
+/// Whether the request failed to connect, so that the server never received it.
+fn is_unsent(result: &Result<reqwest::Response, Error>) -> bool {
+    let Err(err) = result else {
+        return false;
+    };
+    std::error::Error::source(err)
+        .and_then(|source| source.downcast_ref::<reqwest::Error>())
+        .is_some_and(reqwest::Error::is_connect)
+}
+
+/// The wait requested by the `Retry-After` header of a `429` or `503` response, given either in
+/// seconds or as an HTTP date.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    if !matches!(
+        response.status(),
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        return None;
+    }
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTimeParser::new().parse_timestamp(value).ok()?;
+    Some(Duration::try_from(Timestamp::now().duration_until(date)).unwrap_or(Duration::ZERO))
+}
+
+impl Middleware for RetryPolicy {
+    fn handle<'a>(
+        &'a self,
+        request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response, Error>> {
+        Box::pin(async move {
+            let mut request = request;
+            let mut attempts = 1;
+            loop {
+                let retry = request.try_clone().filter(|_| attempts < self.max_attempts);
+                let idempotent = is_idempotent(&request);
+                let result = next.run(request).await;
+                let retry = retry
+                    .filter(|_| idempotent || is_unsent(&result))
+                    .and_then(|retry| Some((retry, self.delay(&result, attempts)?)));
+                let Some((retry, delay)) = retry else {
+                    return result.map_err(|err| {
+                        if attempts > 1 && err.is_temporary() {
+                            err.set_persistent().with_context("attempts", attempts)
+                        } else {
+                            err
+                        }
+                    });
+                };
+                sleep(delay).await;
+                request = retry;
+                attempts += 1;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use super::*;
+    use crate::Client;
+    use crate::ErrorKind;
+    use crate::IngestData;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new()
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(300));
+        for (retry, max) in [(1, 100), (2, 200), (3, 300), (64, 300)] {
+            let backoff = policy.backoff(retry);
+            let max = Duration::from_millis(max);
+            assert!(backoff >= max / 2 && backoff <= max, "{retry}: {backoff:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(502))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .retry_policy(RetryPolicy::new().with_initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+        client.health().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_exhausted() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .retry_policy(
+                RetryPolicy::new()
+                    .with_max_attempts(2)
+                    .with_initial_backoff(Duration::from_millis(1)),
+            )
+            .build()
+            .unwrap();
+        let err = client.health().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+    }
+
+    #[tokio::test]
+    async fn test_retry_ingest_with_idempotency_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(header(IDEMPOTENCY_KEY, "batch-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": 1})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .retry_policy(RetryPolicy::new().with_initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+        let ingest = || {
+            client
+                .ingest_into("t")
+                .data(IngestData::from_json_rows(&[json!({"k": 1})]).unwrap())
+                .transform("SELECT $0")
+        };
+        ingest().idempotency_key("batch-1").send().await.unwrap();
+
+        // Without a key, the first failure is final.
+        server.reset().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+        ingest().send().await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(429).insert_header(RETRY_AFTER, "0"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(503).insert_header(RETRY_AFTER, "60"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The first response is retried at once; waiting a minute exceeds the maximum backoff, so
+        // the second one is returned.
+        let client = Client::builder(server.uri())
+            .retry_policy(RetryPolicy::new().with_initial_backoff(Duration::from_secs(10)))
+            .build()
+            .unwrap();
+        let status = client.health().await.unwrap();
+        assert!(!status.ready);
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        let client = reqwest::Client::new();
+        let is_idempotent =
+            |request: reqwest::RequestBuilder| is_idempotent(&request.build().unwrap());
+        let url = "http://localhost/v1/statements";
+        assert!(is_idempotent(client.get(url)));
+        assert!(is_idempotent(client.post(format!("{url}/0/cancel"))));
+        assert!(!is_idempotent(client.post(url)));
+        assert!(!is_idempotent(
+            client.post(url).json(&json!({"statement": "SELECT 1"}))
+        ));
+        assert!(is_idempotent(client.post(url).json(
+            &json!({"statement_id": "01970000-0000-7000-8000-000000000000", "statement": "SELECT 1"})
+        )));
+        let url = "http://localhost/v1/ingest";
+        assert!(!is_idempotent(client.post(url)));
+        assert!(is_idempotent(client.post(url).header(IDEMPOTENCY_KEY, "k")));
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_connect_error() {
+        // Nothing listens on the port once the listener is dropped.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let client = Client::builder(format!("http://{addr}"))
+            .retry_policy(
+                RetryPolicy::new()
+                    .with_max_attempts(2)
+                    .with_initial_backoff(Duration::from_millis(1)),
+            )
+            .build()
+            .unwrap();
+        let err = client.health().await.unwrap_err();
+        assert!(err.is_persistent());
+        assert_eq!(err.context("attempts"), Some("2"));
+
+        // A submit without a statement id is retried too, since it never reached the server.
+        let err = client
+            .statement("INSERT INTO t VALUES (1)".to_string())
+            .submit()
+            .await
+            .unwrap_err();
+        assert!(err.is_persistent());
+        assert_eq!(err.context("attempts"), Some("2"));
+    }
+}
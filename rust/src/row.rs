@@ -0,0 +1,571 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use crate::Error;
+use crate::ErrorKind;
+use crate::Schema;
+use crate::Value;
+use crate::result::interval_to_duration;
+
+/// Conversion from a single result [`Value`] into a Rust type.
+pub trait FromValue: Sized {
+    fn from_value(value: Value) -> Result<Self, Error>;
+}
+
+/// Conversion from a result row into a Rust type.
+///
+/// This is implemented for tuples up to 12 elements, which map the row's columns positionally.
+/// With the `derive` feature, `#[derive(FromRow)]` implements it for structs, mapping columns to
+/// fields by name.
+pub trait FromRow: Sized {
+    fn from_row(schema: &Schema, row: Vec<Value>) -> Result<Self, Error>;
+
+    /// Convert rows sharing a schema, e.g., to look columns up in the schema only once.
+    fn from_rows(schema: &Schema, rows: Vec<Vec<Value>>) -> Result<Vec<Self>, Error> {
+        rows.into_iter()
+            .map(|row| Self::from_row(schema, row))
+            .collect()
+    }
+}
+
+/// A reference to a column of a [`Row`], either by position or by name.
+pub trait ColumnIndex {
+    fn column_index(&self, schema: &Schema) -> Result<usize, Error>;
+}
+
+impl ColumnIndex for usize {
+    fn column_index(&self, schema: &Schema) -> Result<usize, Error> {
+        let num_fields = schema.fields().len();
+        if *self < num_fields {
+            Ok(*self)
+        } else {
+            Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("column index {self} out of range for {num_fields} columns"),
+            ))
+        }
+    }
+}
+
+impl ColumnIndex for &str {
+    fn column_index(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.index_of(self).ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unexpected,
+                format!("column {self:?} not found in result set"),
+            )
+        })
+    }
+}
+
+/// A result row that knows its schema, so that columns can be read by name.
+///
+/// Convert a result set with `into_typed::<Row>()`.
+#[derive(Debug, Clone)]
+pub struct Row {
+    schema: Schema,
+    values: Vec<Value>,
+}
+
+impl Row {
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<Value> {
+        self.values
+    }
+
+    /// Read a column by position or by name, e.g. `row.get::<i64>("user_id")`.
+    pub fn get<T: FromValue>(&self, index: impl ColumnIndex) -> Result<T, Error> {
+        let i = index.column_index(&self.schema)?;
+        let field = &self.schema.fields()[i];
+        let value = self.values.get(i).cloned().ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unexpected,
+                format!(
+                    "expected {} columns in row, got {}",
+                    self.schema.fields().len(),
+                    self.values.len()
+                ),
+            )
+        })?;
+        T::from_value(value).map_err(|err| err.with_context("column", field.name()))
+    }
+}
+
+impl FromRow for Row {
+    fn from_row(schema: &Schema, row: Vec<Value>) -> Result<Self, Error> {
+        Ok(Row {
+            schema: schema.clone(),
+            values: row,
+        })
+    }
+}
+
+fn mismatch(expected: &str, value: &Value) -> Error {
+    Error::new(
+        ErrorKind::Unexpected,
+        format!("expected {expected} value, got {value:?}"),
+    )
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        Ok(value)
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Int(v) => Ok(v),
+            Value::UInt(v) => i64::try_from(v).map_err(|_| mismatch("int", &value)),
+            value => Err(mismatch("int", &value)),
+        }
+    }
+}
+
+impl FromValue for u64 {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::UInt(v) => Ok(v),
+            Value::Int(v) => u64::try_from(v).map_err(|_| mismatch("uint", &value)),
+            value => Err(mismatch("uint", &value)),
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Float(v) => Ok(v),
+            value => Err(mismatch("float", &value)),
+        }
+    }
+}
+
+impl FromValue for rust_decimal::Decimal {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Decimal(v) => Ok(v),
+            Value::Int(v) => Ok(v.into()),
+            Value::UInt(v) => Ok(v.into()),
+            value => Err(mismatch("decimal", &value)),
+        }
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Boolean(v) => Ok(v),
+            value => Err(mismatch("boolean", &value)),
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::String(v)
+            | Value::Binary(v)
+            | Value::Array(v)
+            | Value::Object(v)
+            | Value::Any(v) => Ok(v),
+            value => Err(mismatch("string", &value)),
+        }
+    }
+}
+
+/// Converts any value into its JSON form with [`Value::to_json`], e.g., to traverse arrays and
+/// objects.
+impl FromValue for serde_json::Value {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        Ok(value.to_json())
+    }
+}
+
+impl FromValue for Vec<u8> {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        value.to_bytes()
+    }
+}
+
+/// Converts array values, inferring the types of their elements as in [`Value::from_json`].
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        value
+            .elements()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, element)| {
+                T::from_value(element).map_err(|err| err.with_context("element", i))
+            })
+            .collect()
+    }
+}
+
+/// Converts object values, inferring the types of their entry values as in
+/// [`Value::from_json`].
+impl<T: FromValue> FromValue for BTreeMap<String, T> {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        value
+            .entries()?
+            .into_iter()
+            .map(|(key, value)| match T::from_value(value) {
+                Ok(value) => Ok((key, value)),
+                Err(err) => Err(err.with_context("key", key)),
+            })
+            .collect()
+    }
+}
+
+impl FromValue for jiff::Timestamp {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Timestamp(v) => Ok(v),
+            value => Err(mismatch("timestamp", &value)),
+        }
+    }
+}
+
+impl FromValue for jiff::Span {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Interval(v) => Ok(v),
+            value => Err(mismatch("interval", &value)),
+        }
+    }
+}
+
+/// Days count as 24 hours; intervals with months or years cannot be converted.
+impl FromValue for jiff::SignedDuration {
+    fn from_value(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Interval(v) => interval_to_duration(&v).map_err(|err| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    format!("cannot convert interval {v} to a duration: {err}"),
+                )
+            }),
+            value => Err(mismatch("interval", &value)),
+        }
+    }
+}
+
+/// The positions of the columns read into the fields of a struct, for code generated by
+/// `#[derive(FromRow)]`, resolved once per schema.
+#[derive(Debug)]
+pub struct ColumnPositions<'a> {
+    names: &'a [&'a str],
+    /// The position of the column of each field, and whether a later field reads it too.
+    positions: Vec<(usize, bool)>,
+    num_columns: usize,
+}
+
+impl<'a> ColumnPositions<'a> {
+    /// Look up the column of each field, given by name, in the schema.
+    pub fn new(schema: &Schema, names: &'a [&'a str]) -> Result<Self, Error> {
+        let indices = names
+            .iter()
+            .map(|name| name.column_index(schema))
+            .collect::<Result<Vec<_>, _>>()?;
+        let positions = indices
+            .iter()
+            .enumerate()
+            .map(|(i, index)| (*index, indices[i + 1..].contains(index)))
+            .collect();
+        Ok(Self {
+            names,
+            positions,
+            num_columns: schema.fields().len(),
+        })
+    }
+
+    /// Check that the row has a value for every column of the schema.
+    pub fn check_row(&self, row: &[Value]) -> Result<(), Error> {
+        if row.len() == self.num_columns {
+            return Ok(());
+        }
+        Err(Error::new(
+            ErrorKind::Unexpected,
+            format!(
+                "expected {} columns in row, got {}",
+                self.num_columns,
+                row.len()
+            ),
+        ))
+    }
+
+    /// Take the value of the column of the `field`-th field out of the row, or copy it if a
+    /// later field reads the same column.
+    pub fn take<T: FromValue>(&self, row: &mut [Value], field: usize) -> Result<T, Error> {
+        let (index, shared) = self.positions[field];
+        let value = if shared {
+            row[index].clone()
+        } else {
+            std::mem::replace(&mut row[index], Value::Null)
+        };
+        T::from_value(value).map_err(|err| err.with_context("column", self.names[field]))
+    }
+}
+
+macro_rules! impl_from_row_for_tuple {
+    ($len:literal => $($name:ident),+) => {
+        impl<$($name: FromValue),+> FromRow for ($($name,)+) {
+            fn from_row(_schema: &Schema, row: Vec<Value>) -> Result<Self, Error> {
+                if row.len() != $len {
+                    return Err(Error::new(
+                        ErrorKind::Unexpected,
+                        format!("expected {} columns in row, got {}", $len, row.len()),
+                    ));
+                }
+
+                let mut values = row.into_iter().enumerate();
+                Ok(($({
+                    let (i, value) = values.next().expect("row length has been checked");
+                    $name::from_value(value).map_err(|err| err.with_context("column", i))?
+                },)+))
+            }
+        }
+    };
+}
+
+impl_from_row_for_tuple!(1 => T0);
+impl_from_row_for_tuple!(2 => T0, T1);
+impl_from_row_for_tuple!(3 => T0, T1, T2);
+impl_from_row_for_tuple!(4 => T0, T1, T2, T3);
+impl_from_row_for_tuple!(5 => T0, T1, T2, T3, T4);
+impl_from_row_for_tuple!(6 => T0, T1, T2, T3, T4, T5);
+impl_from_row_for_tuple!(7 => T0, T1, T2, T3, T4, T5, T6);
+impl_from_row_for_tuple!(8 => T0, T1, T2, T3, T4, T5, T6, T7);
+impl_from_row_for_tuple!(9 => T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_from_row_for_tuple!(10 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_from_row_for_tuple!(11 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_from_row_for_tuple!(12 => T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+#[cfg(test)]
+mod tests {
+    use crate::ResultSet;
+    use crate::protocol::StatementResultSet;
+
+    fn test_result_set(payload: serde_json::Value) -> ResultSet {
+        let result_set: StatementResultSet = serde_json::from_value(payload).unwrap();
+        ResultSet::from_statement_result_set(result_set)
+    }
+
+    fn test_rows() -> ResultSet {
+        test_result_set(serde_json::json!({
+            "metadata": {
+                "fields": [
+                    {"name": "id", "data_type": "int"},
+                    {"name": "name", "data_type": "string"},
+                    {"name": "active", "data_type": "boolean"},
+                ],
+                "num_rows": 2,
+            },
+            "format": "json",
+            "rows": [["1", "alpha", "true"], ["2", null, "false"]],
+        }))
+    }
+
+    #[test]
+    fn test_into_typed_tuples() {
+        let rows = test_rows()
+            .into_typed::<(i64, Option<String>, bool)>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![(1, Some("alpha".to_string()), true), (2, None, false)]
+        );
+    }
+
+    #[test]
+    fn test_into_typed_column_count_mismatch() {
+        let err = test_rows().into_typed::<(i64, String)>().unwrap_err();
+        assert!(err.to_string().contains("expected 2 columns in row, got 3"));
+    }
+
+    #[test]
+    fn test_into_typed_derived() {
+        #[derive(Debug, PartialEq, scopedb_derive::FromRow)]
+        struct User {
+            #[scopedb(rename = "active")]
+            enabled: bool,
+            id: i64,
+            name: Option<String>,
+        }
+
+        let rows = test_rows().into_typed::<User>().unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                User {
+                    enabled: true,
+                    id: 1,
+                    name: Some("alpha".to_string()),
+                },
+                User {
+                    enabled: false,
+                    id: 2,
+                    name: None,
+                },
+            ]
+        );
+
+        #[derive(Debug, scopedb_derive::FromRow)]
+        struct Missing {
+            #[allow(dead_code)]
+            email: String,
+        }
+
+        let err = test_rows().into_typed::<Missing>().unwrap_err();
+        assert!(
+            err.to_string().contains(r#"column "email" not found"#),
+            "{err}"
+        );
+
+        #[derive(Debug, PartialEq, scopedb_derive::FromRow)]
+        struct Aliased {
+            id: i64,
+            #[scopedb(rename = "name")]
+            label: Option<String>,
+            name: Option<String>,
+        }
+
+        let rows = test_rows().into_typed::<Aliased>().unwrap();
+        assert_eq!(
+            rows[0],
+            Aliased {
+                id: 1,
+                label: Some("alpha".to_string()),
+                name: Some("alpha".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_into_typed_json() {
+        let rows = test_result_set(serde_json::json!({
+            "metadata": {
+                "fields": [
+                    {"name": "tags", "data_type": "array"},
+                    {"name": "attrs", "data_type": "object"},
+                ],
+                "num_rows": 1,
+            },
+            "format": "json",
+            "rows": [[r#"["a",1]"#, r#"{"k":{"n":null}}"#]],
+        }))
+        .into_typed::<(serde_json::Value, serde_json::Value)>()
+        .unwrap();
+        let (tags, attrs) = &rows[0];
+        assert_eq!(tags[0], "a");
+        assert_eq!(tags[1], 1);
+        assert!(attrs["k"]["n"].is_null());
+    }
+
+    #[test]
+    fn test_into_typed_nested() {
+        let result_set = test_result_set(serde_json::json!({
+            "metadata": {
+                "fields": [
+                    {"name": "ids", "data_type": "array", "element_type": "int"},
+                    {"name": "seen", "data_type": "object", "element_type": "timestamp"},
+                ],
+                "num_rows": 1,
+            },
+            "format": "json",
+            "rows": [[r#"[1,null,3]"#, r#"{"a":"2024-01-01T00:00:00Z"}"#]],
+        }));
+        let schema = result_set.schema().clone();
+        let rows = result_set.into_typed::<crate::Row>().unwrap();
+        let row = &rows[0];
+
+        assert_eq!(
+            row.get::<Vec<Option<i64>>>("ids").unwrap(),
+            [Some(1), None, Some(3)]
+        );
+        let seen = row.get::<crate::Value>("seen").unwrap();
+        let element_type = schema.fields()[1].element_type().unwrap();
+        let seen = seen.entries_of(element_type).unwrap();
+        assert!(matches!(seen["a"], crate::Value::Timestamp(_)));
+        assert_eq!(seen.len(), 1);
+
+        let err = row.get::<Vec<i64>>("ids").unwrap_err();
+        assert!(err.to_string().contains("element: 1"), "{err}");
+        let err = row.get::<Vec<i64>>("seen").unwrap_err();
+        assert!(err.to_string().contains("expected array value"), "{err}");
+    }
+
+    #[test]
+    fn test_row_get() {
+        let rows = test_rows().into_typed::<crate::Row>().unwrap();
+        let row = &rows[1];
+        assert_eq!(row.get::<i64>("id").unwrap(), 2);
+        assert_eq!(row.get::<i64>(0).unwrap(), 2);
+        assert_eq!(row.get::<Option<String>>("name").unwrap(), None);
+        assert!(!row.get::<bool>(2).unwrap());
+
+        let err = row.get::<bool>("id").unwrap_err();
+        assert!(err.to_string().contains("expected boolean value"), "{err}");
+        assert!(err.to_string().contains("column: id"), "{err}");
+        let err = row.get::<i64>("email").unwrap_err();
+        assert!(err.to_string().contains(r#"column "email" not found"#));
+        let err = row.get::<i64>(3).unwrap_err();
+        assert!(err.to_string().contains("out of range"));
+    }
+
+    #[test]
+    fn test_column() {
+        let result_set = test_rows();
+        assert_eq!(
+            result_set.column::<Option<String>>("name").unwrap(),
+            [Some("alpha".to_string()), None]
+        );
+        assert_eq!(result_set.column::<i64>(0).unwrap(), [1, 2]);
+
+        let err = result_set.column::<String>("name").unwrap_err();
+        assert!(err.to_string().contains("row: 1"), "{err}");
+    }
+
+    #[test]
+    fn test_into_typed_type_mismatch() {
+        let err = test_rows()
+            .into_typed::<(String, Option<String>, bool)>()
+            .unwrap_err();
+        assert!(err.to_string().contains("column: 0"));
+        assert!(err.to_string().contains("expected string value, got 1"));
+
+        let err = test_rows().into_typed::<(i64, String, bool)>().unwrap_err();
+        assert!(err.to_string().contains("column: 1"));
+        assert!(err.to_string().contains("got NULL"));
+    }
+}
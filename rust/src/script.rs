@@ -0,0 +1,65 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lexer;
+use crate::lexer::TokenKind;
+
+/// Split a script into its statements, at the semicolons outside quotes and comments.
+///
+/// Statements are trimmed, and those that are empty or consist only of comments are dropped.
+pub(crate) fn split_statements(script: &str) -> Vec<&str> {
+    let mut statements = vec![];
+    let mut start = 0;
+    let mut has_code = false;
+    for token in lexer::tokenize(script) {
+        if token.kind == TokenKind::Punct && token.text == ";" {
+            if has_code {
+                statements.push(script[start..token.start].trim());
+            }
+            has_code = false;
+            start = token.end();
+        } else {
+            has_code |= token.is_code();
+        }
+    }
+    if has_code {
+        statements.push(script[start..].trim());
+    }
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        let script = r#"
+            -- create the table; then fill it
+            CREATE TABLE t (s string);
+            INSERT INTO t VALUES ('a;b'), ("c\";d");
+            /* a comment; */ ;
+            FROM t -- the rest ; of the line
+        "#;
+        assert_eq!(
+            split_statements(script),
+            [
+                "-- create the table; then fill it\n            CREATE TABLE t (s string)",
+                r#"INSERT INTO t VALUES ('a;b'), ("c\";d")"#,
+                "FROM t -- the rest ; of the line",
+            ]
+        );
+        assert!(split_statements(" ; -- nothing\n").is_empty());
+    }
+}
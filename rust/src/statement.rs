@@ -12,22 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::time::Duration;
+use std::time::Instant;
 
+use futures::Stream;
 use jiff::SignedDuration;
+use mea::semaphore::OwnedSemaphorePermit;
+use serde::Deserialize;
+use serde::Serialize;
 use tokio::time::sleep;
 use uuid::Uuid;
 
 use crate::Error;
 use crate::ErrorKind;
+use crate::PollPolicy;
+use crate::Priority;
+use crate::ResultFormat;
+use crate::ServerError;
 use crate::StatementCancelResult;
+use crate::StatementEstimatedProgress;
+use crate::StatementState;
+use crate::StatementStatusFinished;
+use crate::TimeoutOrigin;
+use crate::Value;
+use crate::cache::CacheKey;
 use crate::client::Client;
 use crate::protocol::Response;
-use crate::protocol::ResultFormat;
+use crate::protocol::StatementCancelRequest;
 use crate::protocol::StatementRequest;
 use crate::protocol::StatementRequestParams;
 use crate::protocol::StatementStatus;
 use crate::result::ResultSet;
+use crate::template::Template;
+use crate::template::literal;
 
 #[derive(Debug)]
 pub struct Statement {
@@ -36,15 +54,27 @@ pub struct Statement {
     statement_id: Option<Uuid>,
     exec_timeout: Option<SignedDuration>,
     max_parallelism: Option<usize>,
-    format: ResultFormat,
+    priority: Option<Priority>,
+    parameters: Vec<Value>,
+    named_parameters: BTreeMap<String, Value>,
+    tags: BTreeMap<String, String>,
+    params: StatementRequestParams,
+    poll_policy: PollPolicy,
+    cache: bool,
 }
 
 impl Statement {
+    /// Set the id of the statement, instead of letting the server assign one.
+    ///
+    /// The server deduplicates submits by id, so a [`RetryPolicy`](crate::RetryPolicy) retries
+    /// a submit that has one.
     pub fn with_statement_id(mut self, statement_id: Uuid) -> Self {
         self.statement_id = Some(statement_id);
         self
     }
 
+    /// Set how long the server may run the statement, overriding the default of the client set
+    /// with [`ClientBuilder::exec_timeout`](crate::ClientBuilder::exec_timeout).
     pub fn with_exec_timeout(mut self, exec_timeout: SignedDuration) -> Self {
         self.exec_timeout = Some(exec_timeout);
         self
@@ -55,33 +85,152 @@ impl Statement {
         self
     }
 
+    /// Hint the server on how to schedule the statement, e.g., to favor interactive queries over
+    /// batch jobs.
+    ///
+    /// The priority is sent in the unconfirmed `priority` request field, which servers may
+    /// ignore.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Set how the statement polls for its status while waiting to terminate, overriding the
+    /// policy of the client.
+    pub fn with_poll_policy(mut self, poll_policy: PollPolicy) -> Self {
+        self.poll_policy = poll_policy;
+        self
+    }
+
+    /// Bind the next positional parameter, referenced as `$1`, `$2`, and so on in the statement.
+    ///
+    /// On submit, values are rendered into the statement as ScopeQL literals of their type, e.g.,
+    /// `'2026-01-01T00:00:00Z'::timestamp`, with strings quoted and escaped. Placeholders in
+    /// string literals and comments are left alone, as are placeholders without a value.
+    pub fn bind(mut self, value: impl Into<Value>) -> Self {
+        self.parameters.push(value.into());
+        self
+    }
+
+    /// Bind a named parameter, referenced as `$name` in the statement.
+    ///
+    /// Binding the same name again replaces its value.
+    pub fn bind_named(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.named_parameters.insert(name.into(), value.into());
+        self
+    }
+
+    /// Label the statement, e.g., with the team or job that runs it, so that it can be attributed
+    /// when listing statements with [`Client::list_statements`].
+    ///
+    /// Tags are sent in the unconfirmed `tags` request field, which servers may ignore.
+    ///
+    /// Tagging the same key again replaces its value.
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Serve [`execute`](Self::execute) from the result cache of the client, if it has one, and
+    /// cache the result on a miss.
+    ///
+    /// Only opt in statements without side effects. See [`ResultCache`](crate::ResultCache).
+    pub fn with_cache(mut self, cache: bool) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Ask the server to return partial results while the statement is running.
+    ///
+    /// This sends the unconfirmed `allow_partial` request field, which servers may ignore, in
+    /// which case running statements report no partial result. See
+    /// [`StatementHandle::partial_result_set`].
+    pub fn with_allow_partial(mut self, allow_partial: bool) -> Self {
+        self.params.allow_partial = allow_partial.then_some(true);
+        self
+    }
+
+    /// Set the wire format of the result set, [`ResultFormat::Json`] by default.
+    pub fn with_result_format(mut self, format: ResultFormat) -> Self {
+        self.params.format = format;
+        self
+    }
+
+    /// Refuse results of more than `max_rows` rows.
+    ///
+    /// The limit is enforced on the client: a finished result that exceeds it fails with
+    /// [`ErrorKind::ResultBudgetExceeded`] when fetched. It is also sent to the server as an
+    /// unconfirmed hint, which servers may ignore.
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.params.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Refuse results larger than `max_result_bytes` bytes.
+    ///
+    /// The limit is enforced on the client: a response body carrying the statement status that
+    /// exceeds it fails with [`ErrorKind::ResultBudgetExceeded`] without being read in full. It is
+    /// also sent to the server as an unconfirmed hint, which servers may ignore.
+    pub fn with_max_result_bytes(mut self, max_result_bytes: usize) -> Self {
+        self.params.max_result_bytes = Some(max_result_bytes);
+        self
+    }
+
+    /// Ask the server to return only `limit` rows of the result, starting from row `offset`.
+    ///
+    /// See [`Client::query_page`] for a helper that also handles servers without pagination.
+    pub fn with_page(mut self, offset: usize, limit: usize) -> Self {
+        self.params.offset = Some(offset);
+        self.params.limit = Some(limit);
+        self
+    }
+
     pub async fn submit(self) -> Result<StatementHandle, Error> {
+        let statement = self.text();
         let Statement {
             client,
-            statement,
+            statement: _,
             statement_id,
             exec_timeout,
             max_parallelism,
-            format,
+            priority,
+            parameters: _,
+            named_parameters: _,
+            tags,
+            params,
+            poll_policy,
+            cache: _,
         } = self;
 
+        let slot = client.acquire_in_flight_slot().await;
+        let started_at = Instant::now();
         let resp = client
             .submit_statement(StatementRequest {
                 statement,
                 statement_id,
                 exec_timeout,
                 max_parallelism,
-                params: StatementRequestParams { format },
+                priority,
+                tags,
+                params: params.clone(),
             })
             .await?;
 
         match resp {
-            Response::Success(response) => Ok(StatementHandle {
-                client,
-                statement_id: response.statement_id(),
-                format,
-                status: Some(response),
-            }),
+            Response::Success(response) => {
+                let mut handle = StatementHandle {
+                    client,
+                    statement_id: response.statement_id(),
+                    params,
+                    status: Some(response),
+                    started_at,
+                    poll_policy,
+                    slot,
+                    page: None,
+                };
+                handle.release_slot_if_terminated();
+                Ok(handle)
+            }
             Response::Failed(err) => Err(Error::new(
                 ErrorKind::Unexpected,
                 format!("failed to submit statement: {err}"),
@@ -89,18 +238,79 @@ impl Statement {
         }
     }
 
+    /// Submit the statement and return its id without waiting for it, e.g., for maintenance
+    /// statements that are checked on later, possibly from another process, with
+    /// [`Client::statement_handle`].
+    ///
+    /// Fails if the server reports the statement as failed or cancelled right away.
+    ///
+    /// Under [`ClientBuilder::max_in_flight`], the statement keeps its slot until it terminates,
+    /// which a background task polls for.
+    ///
+    /// [`ClientBuilder::max_in_flight`]: crate::ClientBuilder::max_in_flight
+    pub async fn submit_detached(self) -> Result<Uuid, Error> {
+        let mut handle = self.submit().await?.check_not_failed()?;
+        let statement_id = handle.statement_id();
+        if handle.slot.is_some() {
+            tokio::spawn(async move {
+                // The slot is freed when the statement terminates or polling fails.
+                let _ = handle.wait(None).await;
+            });
+        }
+        Ok(statement_id)
+    }
+
     pub async fn execute(self) -> Result<ResultSet, Error> {
-        self.submit().await?.fetch().await
+        let cache = match self.cache {
+            true => self.client.result_cache().cloned(),
+            false => None,
+        };
+        let Some(cache) = cache else {
+            return self.submit().await?.fetch_into().await;
+        };
+
+        let key = self.cache_key();
+        if let Some(result_set) = cache.get(&key) {
+            return Ok(result_set);
+        }
+        let result_set = self.submit().await?.fetch_into().await?;
+        cache.insert(key, result_set.clone());
+        Ok(result_set)
+    }
+
+    fn cache_key(&self) -> CacheKey {
+        let parameters = self.parameters.iter().map(literal).collect::<Vec<_>>();
+        let named_parameters = self
+            .named_parameters
+            .iter()
+            .map(|(name, value)| (name, literal(value)))
+            .collect::<BTreeMap<_, _>>();
+        let params = serde_json::json!([parameters, named_parameters, self.params]);
+        CacheKey::new(&self.statement, params.to_string())
+    }
+
+    /// The statement text with the bound parameters rendered into it.
+    fn text(&self) -> String {
+        if self.parameters.is_empty() && self.named_parameters.is_empty() {
+            return self.statement.clone();
+        }
+        Template::parse(self.statement.clone()).render(&self.parameters, &self.named_parameters)
     }
 
     pub(crate) fn new(client: Client, statement: String) -> Self {
         Self {
+            poll_policy: client.poll_policy(),
+            exec_timeout: client.exec_timeout(),
             client,
             statement,
             statement_id: None,
-            exec_timeout: None,
             max_parallelism: None,
-            format: ResultFormat::Json,
+            priority: None,
+            parameters: vec![],
+            named_parameters: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            params: StatementRequestParams::new(ResultFormat::Json),
+            cache: false,
         }
     }
 }
@@ -109,15 +319,91 @@ impl Statement {
 pub struct StatementHandle {
     client: Client,
     statement_id: Uuid,
-    format: ResultFormat,
+    params: StatementRequestParams,
     status: Option<StatementStatus>,
+    started_at: Instant,
+    poll_policy: PollPolicy,
+    /// The slot taken under [`ClientBuilder::max_in_flight`], held until the statement
+    /// terminates.
+    ///
+    /// [`ClientBuilder::max_in_flight`]: crate::ClientBuilder::max_in_flight
+    slot: Option<OwnedSemaphorePermit>,
+    /// The page of the result held by a finished status, as `(offset, limit)`, if it was fetched
+    /// with [`fetch_page`](Self::fetch_page) rather than with the request parameters.
+    page: Option<(usize, usize)>,
+}
+
+/// What a [`StatementHandle`] needs to resume polling its statement, e.g., in a new process
+/// after a crash.
+///
+/// Taken with [`StatementHandle::checkpoint`] and resumed with [`Client::resume_statement`]. It
+/// serializes with serde, so that a worker can persist its in-flight statements.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementCheckpoint {
+    statement_id: Uuid,
+    #[serde(flatten)]
+    params: StatementRequestParams,
+    poll_policy: PollPolicy,
+}
+
+impl StatementCheckpoint {
+    pub fn statement_id(&self) -> Uuid {
+        self.statement_id
+    }
+
+    pub fn result_format(&self) -> ResultFormat {
+        self.params.format
+    }
+
+    pub fn poll_policy(&self) -> PollPolicy {
+        self.poll_policy
+    }
+}
+
+/// The result of a finished statement along with its execution statistics.
+///
+/// Returned by [`StatementHandle::fetch_outcome`].
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct QueryOutcome {
+    pub result_set: ResultSet,
+    /// The final progress of the statement, including its scan statistics.
+    pub progress: StatementEstimatedProgress,
+    pub statement_id: Uuid,
+    /// The time from submitting the statement, or from creating the handle for a statement
+    /// submitted elsewhere, until its result was fetched.
+    pub elapsed: Duration,
 }
 
 impl StatementHandle {
+    /// Set the wire format to fetch the result in, e.g., the format the statement was originally
+    /// submitted with when reattaching to it with [`Client::statement_handle`].
+    pub fn with_result_format(mut self, format: ResultFormat) -> Self {
+        self.params.format = format;
+        self
+    }
+
+    /// Set how the handle polls for the status while waiting for the statement to terminate,
+    /// overriding the policy of the client.
+    pub fn with_poll_policy(mut self, poll_policy: PollPolicy) -> Self {
+        self.poll_policy = poll_policy;
+        self
+    }
+
     pub fn statement_id(&self) -> Uuid {
         self.statement_id
     }
 
+    /// Take a checkpoint of the handle, to resume polling the statement later with
+    /// [`Client::resume_statement`], possibly from another process.
+    pub fn checkpoint(&self) -> StatementCheckpoint {
+        StatementCheckpoint {
+            statement_id: self.statement_id,
+            params: self.params.clone(),
+            poll_policy: self.poll_policy,
+        }
+    }
+
     pub fn status(&self) -> Option<&StatementStatus> {
         self.status.as_ref()
     }
@@ -126,6 +412,32 @@ impl StatementHandle {
         self.status.as_ref().map(StatementStatus::progress)
     }
 
+    /// When the server created the statement, if its status has been fetched.
+    pub fn created_at(&self) -> Option<jiff::Timestamp> {
+        self.status.as_ref().map(StatementStatus::created_at)
+    }
+
+    /// The state of the statement as of the last fetch, if any.
+    pub fn state(&self) -> Option<StatementState> {
+        self.status.as_ref().map(StatementStatus::state)
+    }
+
+    /// Whether the statement has finished, failed or been cancelled as of the last fetch.
+    pub fn is_terminated(&self) -> bool {
+        self.state().is_some_and(|state| state.is_terminated())
+    }
+
+    /// The message explaining why the statement failed or was cancelled, if it did.
+    pub fn failure_message(&self) -> Option<&str> {
+        self.status
+            .as_ref()
+            .and_then(StatementStatus::failure_message)
+    }
+
+    /// Return a copy of the result if the statement has finished.
+    ///
+    /// This clones the whole result; prefer [`into_result_set`](Self::into_result_set) when the
+    /// handle is no longer needed.
     pub fn result_set(&self) -> Option<ResultSet> {
         self.status.as_ref().and_then(|status| match status {
             StatementStatus::Finished(s) => Some(s.result_set()),
@@ -133,25 +445,65 @@ impl StatementHandle {
         })
     }
 
+    /// Consume the handle and return its result if the statement has finished, without copying
+    /// it.
+    pub fn into_result_set(self) -> Option<ResultSet> {
+        match self.status {
+            Some(StatementStatus::Finished(s)) => Some(s.into_result_set()),
+            _ => None,
+        }
+    }
+
+    /// Return the partial result of the running statement, if any.
+    ///
+    /// The statement must be submitted with [`Statement::with_allow_partial`], and the server must
+    /// support partial results, which are not part of the confirmed server API yet. The returned
+    /// result set is marked as [partial].
+    ///
+    /// [partial]: ResultSet::is_partial
+    pub fn partial_result_set(&self) -> Option<ResultSet> {
+        self.status.as_ref().and_then(|status| match status {
+            StatementStatus::Running(s) => s.partial_result_set(),
+            _ => None,
+        })
+    }
+
     pub async fn fetch_once(&mut self) -> Result<(), Error> {
-        // already terminated - no need to fetch again
+        self.fetch_status(None, None).await
+    }
+
+    /// Fetch the status once, asking the server to hold the request for up to `wait` until the
+    /// status changes, and for the given page of the result instead of that of the request
+    /// parameters.
+    async fn fetch_status(
+        &mut self,
+        wait: Option<Duration>,
+        page: Option<(usize, usize)>,
+    ) -> Result<(), Error> {
+        let page = page.filter(|page| Some(*page) != self.params.offset.zip(self.params.limit));
+        // already terminated - no need to fetch again, unless for another page
         match self.status.as_ref() {
-            Some(StatementStatus::Finished(..))
-            | Some(StatementStatus::Failed(..))
-            | Some(StatementStatus::Cancelled(..)) => {
+            Some(StatementStatus::Finished(..)) if self.page == page => return Ok(()),
+            Some(StatementStatus::Failed(..)) | Some(StatementStatus::Cancelled(..)) => {
                 return Ok(());
             }
             _ => {}
         }
 
-        let (format, statement_id) = (self.format, self.statement_id);
+        let mut params = self.params.clone();
+        if let Some((offset, limit)) = page {
+            params.offset = Some(offset);
+            params.limit = Some(limit);
+        }
         match self
             .client
-            .fetch_statement(statement_id, StatementRequestParams { format })
+            .fetch_statement(self.statement_id, params, wait)
             .await?
         {
             Response::Success(status) => {
                 self.status = Some(status);
+                self.page = page;
+                self.release_slot_if_terminated();
                 Ok(())
             }
             Response::Failed(err) => Err(Error::new(
@@ -161,34 +513,272 @@ impl StatementHandle {
         }
     }
 
+    /// Wait for the statement to finish and return a copy of its result.
+    ///
+    /// The handle keeps the result too; use [`fetch_into`](Self::fetch_into) to avoid holding two
+    /// copies of a large result, or [`fetch_outcome`](Self::fetch_outcome) to also get the final
+    /// progress.
     pub async fn fetch(&mut self) -> Result<ResultSet, Error> {
-        let mut delay = Duration::from_millis(5);
-        let max_delay = Duration::from_secs(1);
+        self.wait(None).await?;
+        Ok(self.result_set().expect("statement must have finished"))
+    }
+
+    /// Wait at most `deadline` for the statement to finish and return a copy of its result.
+    ///
+    /// Once the deadline passes, fails with [`TimeoutOrigin::Deadline`] and leaves the statement
+    /// running, so it can be fetched again later. The deadline overrides that of the
+    /// [`PollPolicy`].
+    pub async fn fetch_with_deadline(&mut self, deadline: Duration) -> Result<ResultSet, Error> {
+        self.wait_until(Some(deadline), None).await?;
+        Ok(self.result_set().expect("statement must have finished"))
+    }
+
+    /// Like [`fetch_with_deadline`](Self::fetch_with_deadline), but cancels the statement on the
+    /// server once the deadline passes.
+    ///
+    /// The timeout error is returned either way; a failure to cancel is attached to it as the
+    /// `cancel_error` context.
+    pub async fn fetch_or_cancel(&mut self, deadline: Duration) -> Result<ResultSet, Error> {
+        let err = match self.fetch_with_deadline(deadline).await {
+            // Only time out on the deadline, not on a timed out request.
+            Err(err) if err.kind() == ErrorKind::Timeout(TimeoutOrigin::Deadline) => err,
+            result => return result,
+        };
+        match self.cancel().await {
+            Ok(_) => Err(err.with_context("cancelled", true)),
+            Err(cancel_err) => Err(err.with_context("cancel_error", cancel_err)),
+        }
+    }
+
+    /// Wait for the statement to finish and return its result, consuming the handle.
+    pub async fn fetch_into(mut self) -> Result<ResultSet, Error> {
+        self.wait(None).await?;
+        Ok(self
+            .into_result_set()
+            .expect("statement must have finished"))
+    }
+
+    /// Wait for the statement to finish and return its result along with its final progress and
+    /// the elapsed time, consuming the handle.
+    pub async fn fetch_outcome(mut self) -> Result<QueryOutcome, Error> {
+        self.wait(None).await?;
+        let elapsed = self.started_at.elapsed();
+        let statement_id = self.statement_id;
+        match self.status {
+            Some(StatementStatus::Finished(finished)) => Ok(QueryOutcome {
+                progress: finished.progress.clone(),
+                result_set: finished.into_result_set(),
+                statement_id,
+                elapsed,
+            }),
+            _ => unreachable!("statement must have finished"),
+        }
+    }
+
+    /// Poll the statement according to its [`PollPolicy`], yielding its progress after each poll.
+    ///
+    /// The stream ends after yielding the progress of the terminated statement, or after
+    /// yielding the error of a failed poll; the handle keeps the latest status, so a later
+    /// [`fetch`](Self::fetch) reports the outcome. The deadline of the policy does not apply to
+    /// the stream.
+    pub fn progress_stream(
+        &mut self,
+    ) -> impl Stream<Item = Result<StatementEstimatedProgress, Error>> + Send + '_ {
+        let policy = self.poll_policy;
+        futures::stream::unfold(
+            (self, Some(Duration::ZERO)),
+            move |(handle, delay)| async move {
+                let delay = delay?;
+                sleep(delay).await;
+                if let Err(err) = handle.fetch_once().await {
+                    return Some((Err(err), (handle, None)));
+                }
+                let status = handle.status.as_ref()?;
+                let progress = status.progress().clone();
+                let next_delay = (!status.is_terminated()).then(|| {
+                    if delay.is_zero() {
+                        policy.initial_interval()
+                    } else {
+                        policy.next_interval(delay)
+                    }
+                });
+                Some((Ok(progress), (handle, next_delay)))
+            },
+        )
+    }
+
+    /// Poll the statement until it terminates, failing unless it finished, and fetch the given
+    /// page of its result, if any.
+    pub(crate) async fn wait(&mut self, page: Option<(usize, usize)>) -> Result<(), Error> {
+        self.wait_until(self.poll_policy.deadline(), page).await
+    }
+
+    /// Like [`wait`](Self::wait), but with the given deadline instead of that of the policy.
+    async fn wait_until(
+        &mut self,
+        deadline: Option<Duration>,
+        page: Option<(usize, usize)>,
+    ) -> Result<(), Error> {
+        let policy = self.poll_policy;
+        let expires_at = deadline.map(|deadline| Instant::now() + deadline);
+        let mut delay = policy.initial_interval();
 
         loop {
-            self.fetch_once().await?;
+            let polled_at = Instant::now();
+            let wait = policy.long_poll().map(|wait| match expires_at {
+                Some(expires_at) => wait.min(expires_at.saturating_duration_since(polled_at)),
+                None => wait,
+            });
+            self.fetch_status(wait, page).await?;
 
             if let Some(status) = self.status.as_ref() {
                 match status {
-                    StatementStatus::Finished(finished) => return Ok(finished.result_set()),
-                    StatementStatus::Failed(failed) => {
-                        return Err(Error::new(ErrorKind::Unexpected, failed.message.clone()));
-                    }
-                    StatementStatus::Cancelled(cancelled) => {
-                        return Err(Error::new(ErrorKind::Unexpected, cancelled.message.clone()));
+                    StatementStatus::Finished(finished) => return self.check_max_rows(finished),
+                    StatementStatus::Failed(..) | StatementStatus::Cancelled(..) => {
+                        return Err(terminal_error(status));
                     }
                     StatementStatus::Pending(..) | StatementStatus::Running(..) => {
+                        // Poll once more at the deadline before giving up.
+                        let delay = match (deadline, expires_at) {
+                            (Some(deadline), Some(expires_at)) => {
+                                let remaining =
+                                    expires_at.saturating_duration_since(Instant::now());
+                                if remaining.is_zero() {
+                                    return Err(self.deadline_error(deadline));
+                                }
+                                delay.min(remaining)
+                            }
+                            _ => delay,
+                        };
+                        // A server that held the request has waited already.
+                        let delay = match wait {
+                            Some(_) => delay.saturating_sub(polled_at.elapsed()),
+                            None => delay,
+                        };
                         sleep(delay).await;
-                        if delay < max_delay {
-                            delay = std::cmp::min(delay.saturating_mul(2), max_delay);
-                        }
                     }
                 }
             }
+            delay = policy.next_interval(delay);
+        }
+    }
+
+    fn deadline_error(&self, deadline: Duration) -> Error {
+        Error::new(
+            ErrorKind::Timeout(TimeoutOrigin::Deadline),
+            format!("statement did not terminate within {deadline:?}"),
+        )
+        .with_context("statement_id", self.statement_id)
+        .with_context("deadline", format!("{deadline:?}"))
+        .set_temporary()
+    }
+
+    /// Fetch the latest state of the statement once, returning the rows available so far along
+    /// with the progress.
+    ///
+    /// While the statement is running, the result set holds the rows produced so far and is
+    /// marked as [partial], or is `None` if the server has none to report yet. Once the statement
+    /// finishes, it is the complete result. Partial results are requested from the server for
+    /// this and all later fetches of the handle, with the unconfirmed `allow_partial` parameter;
+    /// a server that ignores it reports no rows until the statement finishes, so only the
+    /// progress is known meanwhile.
+    ///
+    /// [partial]: ResultSet::is_partial
+    pub async fn fetch_partial(
+        &mut self,
+    ) -> Result<(Option<ResultSet>, StatementEstimatedProgress), Error> {
+        self.params.allow_partial = Some(true);
+        self.fetch_once().await?;
+
+        let status = self.status.as_ref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "statement status is unknown after fetch".to_string(),
+            )
+        })?;
+        let result_set = match status {
+            StatementStatus::Pending(..) => None,
+            StatementStatus::Running(running) => running.partial_result_set(),
+            StatementStatus::Finished(finished) => {
+                self.check_max_rows(finished)?;
+                Some(finished.result_set())
+            }
+            StatementStatus::Failed(..) | StatementStatus::Cancelled(..) => {
+                return Err(terminal_error(status));
+            }
+        };
+        Ok((result_set, status.progress().clone()))
+    }
+
+    /// Wait for the statement to finish and fetch `limit` rows of its result, starting from row
+    /// `offset`.
+    ///
+    /// Each call requests a page from the server, so a large result can be pulled page by page
+    /// until `offset` reaches [`ResultSet::total_rows`], or, for a server that does not report
+    /// the total, until a page has fewer than `limit` rows. A server that returns more than
+    /// `limit` rows is taken to have ignored the page and returned the whole result, which is
+    /// sliced on the client.
+    pub async fn fetch_page(&mut self, offset: usize, limit: usize) -> Result<ResultSet, Error> {
+        self.wait(Some((offset, limit))).await?;
+        let result_set = self.result_set().expect("statement must have finished");
+        Ok(result_set.into_page(offset, limit))
+    }
+
+    /// Fail if the statement is known to have failed or been cancelled.
+    pub(crate) fn check_not_failed(self) -> Result<Self, Error> {
+        match &self.status {
+            Some(status @ (StatementStatus::Failed(..) | StatementStatus::Cancelled(..))) => {
+                Err(terminal_error(status))
+            }
+            _ => Ok(self),
         }
     }
 
+    /// Fail if a finished result exceeds the row limit, for servers that do not enforce it.
+    fn check_max_rows(&self, finished: &StatementStatusFinished) -> Result<(), Error> {
+        match self.params.max_rows {
+            Some(max_rows) if finished.num_rows() > max_rows => Err(Error::new(
+                ErrorKind::ResultBudgetExceeded,
+                format!(
+                    "result has {} rows, more than {max_rows}",
+                    finished.num_rows()
+                ),
+            )
+            .with_context("max_rows", max_rows)
+            .with_context("statement_id", self.statement_id)
+            .set_permanent()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Cancel the statement.
+    ///
+    /// Use [`cancel_with_reason`](Self::cancel_with_reason) to let operators see why the statement
+    /// was cancelled, on servers that record it.
     pub async fn cancel(&mut self) -> Result<StatementCancelResult, Error> {
+        self.do_cancel(StatementCancelRequest { reason: None })
+            .await
+    }
+
+    /// Cancel the statement, attaching a reason for the server to record.
+    ///
+    /// The reason is sent in a request body that is not part of the confirmed server API yet;
+    /// servers may ignore it, or reject the request. Use [`cancel`](Self::cancel), which sends no
+    /// body, to be safe.
+    pub async fn cancel_with_reason(
+        &mut self,
+        reason: impl Into<String>,
+    ) -> Result<StatementCancelResult, Error> {
+        self.do_cancel(StatementCancelRequest {
+            reason: Some(reason.into()),
+        })
+        .await
+    }
+
+    async fn do_cancel(
+        &mut self,
+        request: StatementCancelRequest,
+    ) -> Result<StatementCancelResult, Error> {
         if let Some(response) = self.status.as_ref() {
             match response {
                 StatementStatus::Pending(..) | StatementStatus::Running(..) => {}
@@ -198,6 +788,7 @@ impl StatementHandle {
                         created_at: s.created_at,
                         status: "finished".to_string(),
                         message: "statement is finished".to_string(),
+                        reason: None,
                     });
                 }
                 StatementStatus::Failed(s) => {
@@ -206,6 +797,7 @@ impl StatementHandle {
                         created_at: s.created_at,
                         status: "failed".to_string(),
                         message: "statement is failed".to_string(),
+                        reason: None,
                     });
                 }
                 StatementStatus::Cancelled(s) => {
@@ -214,12 +806,17 @@ impl StatementHandle {
                         created_at: s.created_at,
                         status: "cancelled".to_string(),
                         message: "statement is cancelled".to_string(),
+                        reason: None,
                     });
                 }
             }
         }
 
-        match self.client.cancel_statement(self.statement_id).await? {
+        match self
+            .client
+            .cancel_statement(self.statement_id, request)
+            .await?
+        {
             Response::Success(response) => {
                 self.status = match response.status.as_str() {
                     "failed" => Some(StatementStatus::Failed(crate::StatementStatusFailed {
@@ -227,6 +824,7 @@ impl StatementHandle {
                         created_at: response.created_at,
                         progress: crate::StatementEstimatedProgress::default(),
                         message: response.message.clone(),
+                        code: None,
                     })),
                     "cancelled" => Some(StatementStatus::Cancelled(
                         crate::StatementStatusCancelled {
@@ -234,10 +832,13 @@ impl StatementHandle {
                             created_at: response.created_at,
                             progress: crate::StatementEstimatedProgress::default(),
                             message: response.message.clone(),
+                            code: None,
                         },
                     )),
                     _ => self.status.take(),
                 };
+                // The statement has terminated one way or another.
+                self.slot = None;
                 Ok(response)
             }
             Response::Failed(err) => Err(Error::new(
@@ -247,12 +848,824 @@ impl StatementHandle {
         }
     }
 
+    /// Give up the slot taken under [`ClientBuilder::max_in_flight`], if any, before the statement
+    /// terminates.
+    ///
+    /// [`ClientBuilder::max_in_flight`]: crate::ClientBuilder::max_in_flight
+    pub(crate) fn release_slot(mut self) -> Self {
+        self.slot = None;
+        self
+    }
+
+    fn release_slot_if_terminated(&mut self) {
+        if self
+            .status
+            .as_ref()
+            .is_some_and(StatementStatus::is_terminated)
+        {
+            self.slot = None;
+        }
+    }
+
+    pub(crate) fn resume(client: Client, checkpoint: StatementCheckpoint) -> Self {
+        Self {
+            client,
+            statement_id: checkpoint.statement_id,
+            params: checkpoint.params,
+            status: None,
+            started_at: Instant::now(),
+            poll_policy: checkpoint.poll_policy,
+            slot: None,
+            page: None,
+        }
+    }
+
     pub(crate) fn new(client: Client, statement_id: Uuid, format: ResultFormat) -> Self {
         Self {
+            poll_policy: client.poll_policy(),
             client,
             statement_id,
-            format,
+            params: StatementRequestParams::new(format),
             status: None,
+            started_at: Instant::now(),
+            slot: None,
+            page: None,
+        }
+    }
+}
+
+/// Make an error for a failed or cancelled statement status.
+fn terminal_error(status: &StatementStatus) -> Error {
+    let server_error = match status {
+        StatementStatus::Failed(failed) => ServerError::new(
+            failed.statement_id,
+            failed.message.clone(),
+            failed.code.clone(),
+        ),
+        StatementStatus::Cancelled(cancelled) => ServerError::new(
+            cancelled.statement_id,
+            cancelled.message.clone(),
+            cancelled.code.clone(),
+        ),
+        _ => unreachable!("statement must have failed or been cancelled"),
+    };
+    statement_error(server_error)
+}
+
+/// Make an error for a statement that failed or was cancelled on the server.
+fn statement_error(server_error: ServerError) -> Error {
+    Error::new(
+        ErrorKind::StatementFailed,
+        server_error.message().to_string(),
+    )
+    .with_context("statement_id", server_error.statement_id())
+    .set_server_error(server_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_json;
+    use wiremock::matchers::body_partial_json;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::matchers::query_param_is_missing;
+
+    use crate::Client;
+    use crate::ErrorKind;
+    use crate::PollPolicy;
+    use crate::Priority;
+    use crate::ResultCache;
+    use crate::RetryPolicy;
+    use crate::StatementState;
+    use crate::TimeoutOrigin;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_cancel_with_reason() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "/v1/statements/{}/cancel",
+                testing::STATEMENT_ID
+            )))
+            .and(body_json(json!({"reason": "deadline exceeded"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "statement_id": testing::STATEMENT_ID,
+                "status": "cancelled",
+                "message": "statement is cancelled",
+                "created_at": "2026-03-13T12:00:00Z",
+                "reason": "deadline exceeded",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let result = handle
+            .cancel_with_reason("deadline exceeded")
+            .await
+            .unwrap();
+        assert_eq!(result.status, "cancelled");
+        assert_eq!(result.reason.as_deref(), Some("deadline exceeded"));
+        assert!(handle.is_terminated());
+        assert_eq!(handle.state(), Some(StatementState::Cancelled));
+        assert_eq!(handle.failure_message(), Some("statement is cancelled"));
+        assert_eq!(
+            handle.created_at(),
+            Some("2026-03-13T12:00:00Z".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partial_result_set() {
+        let server = MockServer::start().await;
+        let mut running = testing::status("running");
+        running["result_set"] = testing::result_set(&[("count", "int")], json!([["10"]]));
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"allow_partial": true})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(running))
+            .mount(&server)
+            .await;
+        let mut running = testing::status("running");
+        running["result_set"] = testing::result_set(&[("count", "int")], json!([["20"]]));
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("allow_partial", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(running))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut handle = client
+            .statement("FROM t SELECT count()".to_string())
+            .with_allow_partial(true)
+            .submit()
+            .await
+            .unwrap();
+        let partial = handle.partial_result_set().unwrap();
+        assert!(partial.is_partial());
+        assert_eq!(format!("{:?}", partial.into_values().unwrap()), "[[10]]");
+
+        handle.fetch_once().await.unwrap();
+        let partial = handle.partial_result_set().unwrap();
+        assert_eq!(format!("{:?}", partial.into_values().unwrap()), "[[20]]");
+        assert!(handle.result_set().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_into_result_set() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let handle = client
+            .statement("SELECT 1".to_string())
+            .submit()
+            .await
+            .unwrap();
+        assert!(handle.result_set().is_none());
+        let result_set = handle.fetch_into().await.unwrap();
+        assert_eq!(format!("{:?}", result_set.into_values().unwrap()), "[[1]]");
+    }
+
+    #[tokio::test]
+    async fn test_submit_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(
+                json!({"statement_id": testing::STATEMENT_ID}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .retry_policy(RetryPolicy::new().with_initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+        // Without a statement id, the server may have run the statement, so it is sent once.
+        client
+            .statement("INSERT INTO t VALUES (1)".to_string())
+            .submit()
+            .await
+            .unwrap_err();
+        // With one, the server deduplicates the submits.
+        let handle = client
+            .statement("INSERT INTO t VALUES (1)".to_string())
+            .with_statement_id(testing::STATEMENT_ID.parse().unwrap())
+            .submit()
+            .await
+            .unwrap();
+        assert_eq!(handle.statement_id().to_string(), testing::STATEMENT_ID);
+    }
+
+    #[tokio::test]
+    async fn test_submit_detached() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"statement": "OPTIMIZE t"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("pending")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"statement": "OPTIMIZE u"})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(testing::failed("table u not found")),
+            )
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let statement_id = client
+            .statement("OPTIMIZE t".to_string())
+            .submit_detached()
+            .await
+            .unwrap();
+        assert_eq!(statement_id.to_string(), testing::STATEMENT_ID);
+
+        let err = client
+            .statement("OPTIMIZE u".to_string())
+            .submit_detached()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::StatementFailed);
+    }
+
+    #[tokio::test]
+    async fn test_submit_detached_max_in_flight() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(testing::finished(testing::result_set(
+                        &[("v", "int")],
+                        json!([]),
+                    )))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri())
+            .max_in_flight(1)
+            .build()
+            .unwrap();
+        client
+            .statement("OPTIMIZE t".to_string())
+            .submit_detached()
+            .await
+            .unwrap();
+        // The detached statement holds the slot until it is seen to finish.
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(100),
+            client.statement("OPTIMIZE u".to_string()).submit(),
+        )
+        .await;
+        assert!(blocked.is_err());
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            client.statement("OPTIMIZE u".to_string()).submit(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_outcome() {
+        let server = MockServer::start().await;
+        let mut finished = testing::finished(testing::result_set(&[("v", "int")], json!([["1"]])));
+        finished["progress"]["scanned_rows"] = json!(42);
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(finished))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let outcome = handle.fetch_outcome().await.unwrap();
+        assert_eq!(outcome.statement_id.to_string(), testing::STATEMENT_ID);
+        assert_eq!(outcome.progress.details.scanned_rows, 42);
+        assert_eq!(outcome.result_set.num_rows(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_progress_stream() {
+        use futures::StreamExt;
+
+        let server = MockServer::start().await;
+        for percentage in [10.0, 60.0] {
+            let mut running = testing::status("running");
+            running["progress"]["total_percentage"] = json!(percentage);
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(running))
+                .up_to_n_times(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let percentages = handle
+            .progress_stream()
+            .map(|progress| progress.unwrap().total_percentage)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(percentages, [10.0, 60.0, 100.0]);
+        assert_eq!(handle.state(), Some(StatementState::Finished));
+
+        let server = MockServer::start().await;
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let progress = handle.progress_stream().collect::<Vec<_>>().await;
+        assert_eq!(progress.len(), 1);
+        assert!(progress[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_partial() {
+        let server = MockServer::start().await;
+        let mut running = testing::status("running");
+        running["progress"]["total_percentage"] = json!(50.0);
+        running["result_set"] = testing::result_set(&[("v", "int")], json!([["1"]]));
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("allow_partial", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(running))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("allow_partial", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"], ["2"]])),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let (result_set, progress) = handle.fetch_partial().await.unwrap();
+        let result_set = result_set.unwrap();
+        assert!(result_set.is_partial());
+        assert_eq!(format!("{:?}", result_set.into_values().unwrap()), "[[1]]");
+        assert_eq!(progress.total_percentage, 50.0);
+
+        let (result_set, progress) = handle.fetch_partial().await.unwrap();
+        let result_set = result_set.unwrap();
+        assert!(!result_set.is_partial());
+        assert_eq!(result_set.num_rows(), 2);
+        assert_eq!(progress.total_percentage, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_page() {
+        let server = MockServer::start().await;
+        for (offset, rows) in [(0, json!([["1"], ["2"]])), (2, json!([["3"]]))] {
+            let mut page = testing::result_set(&[("v", "int")], rows);
+            page["metadata"]["total_rows"] = json!(3);
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+                .and(query_param("offset", offset.to_string()))
+                .and(query_param("limit", "2"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(page)))
+                .expect(1)
+                .mount(&server)
+                .await;
         }
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param_is_missing("offset"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"], ["2"], ["3"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let mut values = vec![];
+        let mut offset = 0;
+        loop {
+            let page = handle.fetch_page(offset, 2).await.unwrap();
+            let total_rows = page.total_rows().unwrap();
+            offset += page.num_rows();
+            values.extend(page.into_values().unwrap());
+            if offset >= total_rows {
+                break;
+            }
+        }
+        assert_eq!(format!("{values:?}"), "[[1], [2], [3]]");
+
+        // The pages do not stick to the handle.
+        let result_set = handle.fetch().await.unwrap();
+        assert_eq!(result_set.num_rows(), 3);
+        assert_eq!(handle.fetch().await.unwrap().num_rows(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_page_without_total() {
+        let server = MockServer::start().await;
+        for (offset, rows) in [(0, json!([["1"], ["2"]])), (2, json!([["3"]]))] {
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+                .and(query_param("offset", offset.to_string()))
+                .and(query_param("limit", "2"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                    testing::result_set(&[("v", "int")], rows),
+                )))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        // The server honors the pages without reporting the total, so the pages are taken as is
+        // until one falls short.
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let mut values = vec![];
+        let mut offset = 0;
+        loop {
+            let page = handle.fetch_page(offset, 2).await.unwrap();
+            assert_eq!(page.total_rows(), None);
+            let num_rows = page.num_rows();
+            offset += num_rows;
+            values.extend(page.into_values().unwrap());
+            if num_rows < 2 {
+                break;
+            }
+        }
+        assert_eq!(format!("{values:?}"), "[[1], [2], [3]]");
+    }
+
+    #[tokio::test]
+    async fn test_bind() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({
+                "statement": "FROM t WHERE name = 'O\\'Brien' AND age > 30 \
+                              AND ts < '2026-03-13T12:00:00Z'::timestamp AND note = '$1'",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let until: jiff::Timestamp = "2026-03-13T12:00:00Z".parse().unwrap();
+        let result_set = client
+            .statement(
+                "FROM t WHERE name = $1 AND age > $2 AND ts < $until AND note = '$1'".to_string(),
+            )
+            .bind("O'Brien")
+            .bind(30)
+            .bind_named("until", until)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(result_set.num_rows(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_with_tag_and_priority() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({
+                "statement": "SELECT 1",
+                "tags": {"job": "nightly", "team": "billing"},
+                "priority": "batch",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        client
+            .statement("SELECT 1".to_string())
+            .with_tag("team", "billing")
+            .with_tag("job", "nightly")
+            .with_priority(Priority::Batch)
+            .submit()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_with_cache() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let cache = ResultCache::new(16, Duration::from_secs(60));
+        let client = Client::builder(server.uri())
+            .result_cache(cache.clone())
+            .build()
+            .unwrap();
+        let execute = |statement: &str, cached: bool| {
+            client
+                .statement(statement.to_string())
+                .bind(1)
+                .with_cache(cached)
+                .execute()
+        };
+        execute("FROM t WHERE k = $1", true).await.unwrap();
+        execute("FROM t\nWHERE k = $1;", true).await.unwrap();
+        assert_eq!(cache.len(), 1);
+        // Not opted in.
+        execute("FROM t WHERE k = $1", false).await.unwrap();
+
+        cache.invalidate("FROM t WHERE k = $1");
+        let result_set = execute("FROM t WHERE k = $1", true).await.unwrap();
+        assert_eq!(result_set.column::<i64>("v").unwrap(), [1]);
+    }
+
+    #[tokio::test]
+    async fn test_poll_policy_deadline() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+
+        let policy = PollPolicy::fixed(Duration::from_millis(20)).with_deadline(Duration::ZERO);
+        let client = Client::builder(server.uri())
+            .poll_policy(policy)
+            .build()
+            .unwrap();
+        let start = Instant::now();
+        let err = client
+            .statement("SELECT 1".to_string())
+            .with_poll_policy(policy.with_deadline(Duration::from_millis(100)))
+            .execute()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Timeout(TimeoutOrigin::Deadline));
+        assert!(err.is_temporary());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        let polls = server.received_requests().await.unwrap().len();
+        // One submission, then polls every 20ms up to the deadline.
+        assert!((4..=8).contains(&polls), "{polls}");
+
+        let err = client
+            .statement_handle(testing::STATEMENT_ID.parse().unwrap())
+            .fetch_into()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Timeout(TimeoutOrigin::Deadline));
+    }
+
+    #[tokio::test]
+    async fn test_long_poll() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("wait_ms", "30000"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(testing::status("running"))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("wait_ms", "30000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let policy =
+            PollPolicy::fixed(Duration::from_millis(200)).with_long_poll(Duration::from_secs(30));
+        let client = Client::builder(server.uri())
+            .poll_policy(policy)
+            .build()
+            .unwrap();
+        let handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let result_set = handle.fetch_into().await.unwrap();
+        assert_eq!(result_set.num_rows(), 1);
+        // Both polls asked the server to hold them, as the mocks only match with `wait_ms`.
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_or_cancel() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "/v1/statements/{}/cancel",
+                testing::STATEMENT_ID
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "statement_id": testing::STATEMENT_ID,
+                "status": "cancelled",
+                "message": "statement is cancelled",
+                "created_at": "2026-03-13T12:00:00Z",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut handle = client.statement_handle(testing::STATEMENT_ID.parse().unwrap());
+        let err = handle
+            .fetch_with_deadline(Duration::from_millis(30))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Timeout(TimeoutOrigin::Deadline));
+        assert_eq!(handle.state(), Some(StatementState::Running));
+
+        let err = handle
+            .fetch_or_cancel(Duration::from_millis(30))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Timeout(TimeoutOrigin::Deadline));
+        assert_eq!(err.context("cancelled"), Some("true"));
+        assert_eq!(handle.state(), Some(StatementState::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_max_rows() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"max_rows": 1})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"], ["2"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let err = client
+            .statement("FROM t".to_string())
+            .with_max_rows(1)
+            .execute()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ResultBudgetExceeded);
+        assert_eq!(err.context("max_rows"), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_max_result_bytes() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"max_result_bytes": 4096})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        let rows = (0..1000).map(|i| [i.to_string()]).collect::<Vec<_>>();
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("max_result_bytes", "4096"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!(rows)),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let err = client
+            .statement("FROM t".to_string())
+            .with_max_result_bytes(4096)
+            .execute()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ResultBudgetExceeded);
+        assert_eq!(err.context("max_result_bytes"), Some("4096"));
+    }
+
+    #[tokio::test]
+    async fn test_server_error() {
+        let server = MockServer::start().await;
+        let mut failed = testing::failed("column `x` not found");
+        failed["code"] = json!("InvalidStatement");
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(failed))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let err = client
+            .statement("FROM t SELECT x".to_string())
+            .execute()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::StatementFailed);
+        let server_error = err.server_error().unwrap();
+        assert_eq!(
+            server_error.statement_id().to_string(),
+            testing::STATEMENT_ID
+        );
+        assert_eq!(server_error.message(), "column `x` not found");
+        assert_eq!(server_error.code(), Some("InvalidStatement"));
+    }
+
+    #[tokio::test]
+    async fn test_client_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(testing::status("running"))
+                    .set_delay(std::time::Duration::from_secs(5)),
+            )
+            .mount(&server)
+            .await;
+
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let client = Client::new(server.uri(), http).unwrap();
+        let err = client
+            .statement("SELECT 1".to_string())
+            .execute()
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Timeout(TimeoutOrigin::Client));
+        assert!(err.is_temporary());
     }
 }
@@ -0,0 +1,516 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+use futures::Stream;
+use futures::stream::BoxStream;
+
+use crate::Error;
+use crate::ErrorKind;
+use crate::ResultSet;
+use crate::Statement;
+use crate::StatementHandle;
+use crate::StatementStatus;
+use crate::Value;
+use crate::result::Rows;
+
+/// A stream of rows returned by [`Client::query_iter`](crate::Client::query_iter) or
+/// [`ResultSet::into_stream`].
+///
+/// For a query, the statement is submitted when the stream is first polled. Once it finishes, its
+/// result is fetched in pages of up to 10,000 rows, each fetched when the rows before it have
+/// been consumed, until the rows reported in [`ResultSet::total_rows`] are fetched or, without a
+/// total, until a page falls short; a server without pagination returns the whole result at
+/// once, see [`StatementHandle::fetch_page`]. Dropping the stream before the statement terminates
+/// cancels the statement on the server.
+///
+/// Rows are converted into values one by one as the stream is polled, and the stream can be
+/// bounded with [`with_max_rows`](Self::with_max_rows) and
+/// [`with_max_bytes`](Self::with_max_bytes).
+pub struct RowStream {
+    source: Option<Source>,
+    budget: Budget,
+    inner: Option<BoxStream<'static, Result<Vec<Value>, Error>>>,
+}
+
+enum Source {
+    Statement(Statement),
+    ResultSet(ResultSet),
+}
+
+/// The number of rows fetched per page of a query result.
+const PAGE_ROWS: usize = 10_000;
+
+impl RowStream {
+    pub(crate) fn new(statement: Statement) -> Self {
+        Self {
+            source: Some(Source::Statement(statement)),
+            budget: Budget::default(),
+            inner: None,
+        }
+    }
+
+    pub(crate) fn from_result_set(result_set: ResultSet) -> Self {
+        Self {
+            source: Some(Source::ResultSet(result_set)),
+            budget: Budget::default(),
+            inner: None,
+        }
+    }
+
+    /// Fail the stream with [`ErrorKind::ResultBudgetExceeded`] instead of yielding more than
+    /// `max_rows` rows.
+    ///
+    /// For a query, pages are requested with no more rows than needed to detect the overrun. The
+    /// budget only takes effect if set before the stream is first polled.
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.budget.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Fail the stream with [`ErrorKind::ResultBudgetExceeded`] instead of yielding rows whose
+    /// accumulated size exceeds `max_bytes`.
+    ///
+    /// The size of a row is the length of its cells as encoded by the server, or an estimate from
+    /// the memory of its record batch for Arrow results. For a query, the budget is also set as
+    /// the statement's [`max_result_bytes`](Statement::with_max_result_bytes), so a response
+    /// larger than the budget fails while its body is read, and the statement is cancelled if it
+    /// is still running. The budget only takes effect if set before the stream is first polled.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.budget.max_bytes = Some(max_bytes);
+        self
+    }
+}
+
+impl Stream for RowStream {
+    type Item = Result<Vec<Value>, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let inner = this.inner.get_or_insert_with(|| {
+            let state = match this.source.take().expect("source must be present") {
+                Source::Statement(statement) => State::Submit(statement, this.budget),
+                Source::ResultSet(result_set) => State::Rows(
+                    BudgetedRows::new(result_set.into_rows(), Tally::new(this.budget)),
+                    None,
+                ),
+            };
+            Box::pin(futures::stream::unfold(state, next_row))
+        });
+        inner.as_mut().poll_next(cx)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Budget {
+    max_rows: Option<usize>,
+    max_bytes: Option<usize>,
+}
+
+/// The rows and bytes yielded so far, against the budget.
+#[derive(Debug, Clone, Copy)]
+struct Tally {
+    budget: Budget,
+    num_rows: usize,
+    num_bytes: usize,
+}
+
+impl Tally {
+    fn new(budget: Budget) -> Self {
+        Self {
+            budget,
+            num_rows: 0,
+            num_bytes: 0,
+        }
+    }
+
+    /// The number of rows to request for the next page: enough to detect a `max_rows` overrun,
+    /// and no more.
+    fn page_rows(&self) -> usize {
+        match self.budget.max_rows {
+            Some(max_rows) => PAGE_ROWS.min(max_rows.saturating_sub(self.num_rows) + 1),
+            None => PAGE_ROWS,
+        }
+    }
+
+    /// The number of rows to request for the page at `offset`.
+    ///
+    /// Unless the server reported the total number of rows, pages after the first hold one row
+    /// less than a full first page, so that a server that ignores pages, and returns the whole
+    /// result again, returns more rows than requested.
+    fn page_limit(&self, offset: usize, counted: bool) -> usize {
+        match (offset, counted) {
+            (0, _) | (_, true) => self.page_rows(),
+            (_, false) => self.page_rows().min(PAGE_ROWS - 1),
+        }
+    }
+}
+
+struct BudgetedRows {
+    rows: Rows,
+    tally: Tally,
+}
+
+impl BudgetedRows {
+    fn new(rows: Rows, tally: Tally) -> Self {
+        Self { rows, tally }
+    }
+
+    fn next(&mut self) -> Option<Result<Vec<Value>, Error>> {
+        let (row, size) = match self.rows.next_sized()? {
+            Ok(row) => row,
+            Err(err) => return Some(Err(err)),
+        };
+        let Tally {
+            budget,
+            num_rows,
+            num_bytes,
+        } = self.tally;
+        let num_rows = num_rows + 1;
+        let num_bytes = num_bytes + size;
+
+        let exceeded = match budget {
+            Budget {
+                max_rows: Some(max),
+                ..
+            } if num_rows > max => Some(("max_rows", max)),
+            Budget {
+                max_bytes: Some(max),
+                ..
+            } if num_bytes > max => Some(("max_bytes", max)),
+            _ => None,
+        };
+        if let Some((key, max)) = exceeded {
+            let err = Error::new(
+                ErrorKind::ResultBudgetExceeded,
+                format!("result budget exceeded after {} rows", self.tally.num_rows),
+            )
+            .with_context(key, max)
+            .set_permanent();
+            return Some(Err(err));
+        }
+
+        self.tally.num_rows = num_rows;
+        self.tally.num_bytes = num_bytes;
+        Some(Ok(row))
+    }
+}
+
+/// The statement, the offset of the next page of its result to fetch, and whether the server
+/// reported the total number of rows.
+struct NextPage(CancelOnDrop, usize, bool);
+
+enum State {
+    Submit(Statement, Budget),
+    Fetch(NextPage, Tally),
+    Rows(BudgetedRows, Option<NextPage>),
+    Done,
+}
+
+async fn next_row(mut state: State) -> Option<(Result<Vec<Value>, Error>, State)> {
+    loop {
+        state = match state {
+            State::Submit(mut statement, budget) => {
+                let tally = Tally::new(budget);
+                if let Some(max_bytes) = budget.max_bytes {
+                    statement = statement.with_max_result_bytes(max_bytes);
+                }
+                match statement.with_page(0, tally.page_rows()).submit().await {
+                    Ok(handle) => {
+                        State::Fetch(NextPage(CancelOnDrop(Some(handle)), 0, false), tally)
+                    }
+                    Err(err) => return Some((Err(err), State::Done)),
+                }
+            }
+            State::Fetch(NextPage(mut guard, offset, counted), tally) => {
+                let handle = guard.0.as_mut().expect("statement handle must be present");
+                let limit = tally.page_limit(offset, counted);
+                if let Err(err) = handle.wait(Some((offset, limit))).await {
+                    return Some((Err(err), State::Done));
+                }
+                let page = handle.result_set().expect("statement must have finished");
+                let num_rows = page.num_rows();
+                let counted = page.total_rows().is_some();
+                let more = match page.total_rows() {
+                    Some(total_rows) => num_rows > 0 && offset + num_rows < total_rows,
+                    // More than requested is the whole result, from a server that ignores pages;
+                    // a full page may be followed by more rows.
+                    None if num_rows > limit => {
+                        if offset > 0 {
+                            // The whole result was already yielded as the first page.
+                            return None;
+                        }
+                        false
+                    }
+                    None => num_rows == limit,
+                };
+                let next = more.then(|| NextPage(guard, offset + num_rows, counted));
+                State::Rows(BudgetedRows::new(page.into_rows(), tally), next)
+            }
+            State::Rows(mut rows, next) => match rows.next() {
+                Some(Ok(row)) => return Some((Ok(row), State::Rows(rows, next))),
+                Some(Err(err)) => return Some((Err(err), State::Done)),
+                None => match next {
+                    Some(next) => State::Fetch(next, rows.tally),
+                    None => return None,
+                },
+            },
+            State::Done => return None,
+        };
+    }
+}
+
+/// Cancel the statement on drop if it has not terminated yet.
+struct CancelOnDrop(Option<StatementHandle>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        let Some(mut handle) = self.0.take() else {
+            return;
+        };
+        if handle.status().is_some_and(StatementStatus::is_terminated) {
+            return;
+        }
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = handle.cancel().await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::StreamExt;
+    use futures::TryStreamExt;
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_partial_json;
+    use wiremock::matchers::body_string;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+
+    use crate::Client;
+    use crate::ErrorKind;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_query_iter() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "int")], json!([["1"], ["2"]])),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let rows = client
+            .query_iter("SELECT 1")
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(format!("{rows:?}"), "[[1], [2]]");
+    }
+
+    #[tokio::test]
+    async fn test_query_iter_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"offset": 0, "limit": 10_000})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        for (offset, rows) in [(0, json!([["1"], ["2"]])), (2, json!([["3"]]))] {
+            let mut page = testing::result_set(&[("v", "int")], rows);
+            page["metadata"]["total_rows"] = json!(3);
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+                .and(query_param("offset", offset.to_string()))
+                .and(query_param("limit", "10000"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(page)))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut stream = client.query_iter("SELECT 1");
+        assert_eq!(
+            format!("{:?}", stream.next().await.unwrap().unwrap()),
+            "[1]"
+        );
+        assert_eq!(
+            format!("{:?}", stream.next().await.unwrap().unwrap()),
+            "[2]"
+        );
+        // The second page is fetched only once the first one is consumed.
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+        assert_eq!(
+            format!("{:?}", stream.next().await.unwrap().unwrap()),
+            "[3]"
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_iter_pages_without_total() {
+        let rows = |range: std::ops::Range<usize>| {
+            let rows = range.map(|v| json!([v.to_string()])).collect::<Vec<_>>();
+            testing::finished(testing::result_set(&[("v", "int")], json!(rows)))
+        };
+
+        // The server honors the pages without reporting the total.
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rows(0..10_000)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .and(query_param("offset", "10000"))
+            .and(query_param("limit", "9999"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rows(10_000..10_001)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let values = client
+            .query_iter("SELECT 1")
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(values.len(), 10_001);
+        assert_eq!(format!("{:?}", values[10_000]), "[10000]");
+
+        // The server ignores the pages and returns the whole result again.
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rows(0..10_000)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rows(0..10_000)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let values = client
+            .query_iter("SELECT 1")
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(values.len(), 10_000);
+    }
+
+    #[tokio::test]
+    async fn test_query_iter_budget() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"limit": 3})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "string")], json!([["aa"], ["bb"], ["cc"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .and(body_partial_json(json!({"max_result_bytes": 5})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::finished(
+                testing::result_set(&[("v", "string")], json!([["aa"], ["bb"], ["cc"]])),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut stream = client.query_iter("SELECT 1").with_max_rows(2);
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.next().await.unwrap().is_ok());
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ResultBudgetExceeded);
+        assert!(
+            err.to_string()
+                .contains("result budget exceeded after 2 rows")
+        );
+        assert!(stream.next().await.is_none());
+
+        // The response body is larger than the budget, so it is not read in full.
+        let mut stream = client.query_iter("SELECT 1").with_max_bytes(5);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ResultBudgetExceeded);
+        assert_eq!(err.context("max_result_bytes"), Some("5"));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_iter_cancels_on_drop() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/statements"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/statements/{}", testing::STATEMENT_ID)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(testing::status("running")))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "/v1/statements/{}/cancel",
+                testing::STATEMENT_ID
+            )))
+            // A plain cancel, without the unconfirmed reason body.
+            .and(body_string(""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "statement_id": testing::STATEMENT_ID,
+                "status": "cancelled",
+                "message": "statement is cancelled",
+                "created_at": "2026-03-13T12:00:00Z",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut stream = client.query_iter("SELECT 1");
+        let next = tokio::time::timeout(Duration::from_millis(100), stream.next()).await;
+        assert!(next.is_err());
+        drop(stream);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.verify().await;
+    }
+}
@@ -124,10 +124,16 @@ impl Table {
             fields.push(FieldSchema {
                 name: column_name,
                 data_type,
+                precision: None,
+                scale: None,
+                nullable: None,
+                element_type: None,
             });
         }
 
-        Ok(Schema { fields })
+        Ok(Schema {
+            fields: fields.into(),
+        })
     }
 }
 
@@ -135,7 +141,7 @@ fn quote_ident(input: &str, quote: char) -> String {
     quote_scopeql(input, quote)
 }
 
-fn quote_string_literal(input: &str) -> String {
+pub(crate) fn quote_string_literal(input: &str) -> String {
     quote_scopeql(input, '\'')
 }
 
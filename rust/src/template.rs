@@ -0,0 +1,178 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::Value;
+use crate::lexer;
+use crate::lexer::TokenKind;
+use crate::table::quote_string_literal;
+
+/// A placeholder in a statement template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Placeholder {
+    /// A positional placeholder, `$1`, `$2`, and so on.
+    Positional(usize),
+    /// A named placeholder such as `$name`.
+    Named(String),
+}
+
+/// A statement with its placeholders located, to render with values bound to them.
+///
+/// The protocol takes no parameters apart from the statement text, so values are rendered into
+/// the statement as ScopeQL literals of their type, e.g., `'2026-01-01T00:00:00Z'::timestamp`.
+/// Placeholders in string literals, quoted identifiers and comments are left alone, as is `$0`,
+/// which refers to the ingested row in transform statements.
+#[derive(Debug, Clone)]
+pub(crate) struct Template {
+    text: String,
+    placeholders: Vec<(Range<usize>, Placeholder)>,
+}
+
+impl Template {
+    pub(crate) fn parse(text: String) -> Self {
+        let placeholders = lexer::tokenize(&text)
+            .filter(|token| token.kind == TokenKind::Word)
+            .filter_map(|token| {
+                let name = token.text.strip_prefix('$')?;
+                let placeholder = if name.starts_with(|c: char| c.is_ascii_digit()) {
+                    Placeholder::Positional(name.parse().ok().filter(|n| *n > 0)?)
+                } else if name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    Placeholder::Named(name.to_string())
+                } else {
+                    return None;
+                };
+                Some((token.start..token.end(), placeholder))
+            })
+            .collect();
+        Self { text, placeholders }
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn placeholders(&self) -> impl Iterator<Item = &Placeholder> {
+        self.placeholders.iter().map(|(_, placeholder)| placeholder)
+    }
+
+    /// Render the statement with `values` bound to the positional placeholders, in order, and
+    /// `entries` to the named ones. Placeholders without a value are kept as is.
+    pub(crate) fn render(&self, values: &[Value], entries: &BTreeMap<String, Value>) -> String {
+        let mut rendered = String::with_capacity(self.text.len());
+        let mut end = 0;
+        for (range, placeholder) in &self.placeholders {
+            let value = match placeholder {
+                Placeholder::Positional(n) => values.get(n - 1),
+                Placeholder::Named(name) => entries.get(name),
+            };
+            if let Some(value) = value {
+                rendered.push_str(&self.text[end..range.start]);
+                rendered.push_str(&literal(value));
+                end = range.end;
+            }
+        }
+        rendered.push_str(&self.text[end..]);
+        rendered
+    }
+}
+
+/// Render a value as a ScopeQL literal of its type.
+pub(crate) fn literal(value: &Value) -> String {
+    match value {
+        // Negative numbers are parenthesized, so that `a-$1` does not render as a `--` comment.
+        Value::Int(v) if *v < 0 => format!("({v})"),
+        Value::Int(v) => v.to_string(),
+        Value::UInt(v) => format!("{v}::uint"),
+        Value::Float(v) if v.is_finite() && v.is_sign_negative() => format!("({v:?}::float)"),
+        Value::Float(v) if v.is_finite() => format!("{v:?}::float"),
+        // NaN and infinities have no numeric literal.
+        Value::Float(v) => format!("'{v}'::float"),
+        Value::Decimal(v) => format!("'{v}'::decimal"),
+        Value::Timestamp(v) => format!("'{v}'::timestamp"),
+        Value::Interval(v) => format!("'{v}'::interval"),
+        Value::Boolean(v) => v.to_string(),
+        Value::String(v) => quote_string_literal(v),
+        Value::Binary(v) => format!("{}::binary", quote_string_literal(v)),
+        Value::Array(v) => format!("parse_json({})::array", quote_string_literal(v)),
+        Value::Object(v) => format!("parse_json({})::object", quote_string_literal(v)),
+        Value::Any(v) => format!("parse_json({})", quote_string_literal(v)),
+        Value::Null => "NULL".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let template = Template::parse(
+            "FROM t WHERE a = $2 AND b = $1 AND c = $name_1 AND d = $0 \
+             AND e = '$1' -- $1\n AND f = $other"
+                .to_string(),
+        );
+        let values = [Value::String("it's".to_string()), Value::Float(f64::NAN)];
+        let entries = BTreeMap::from([(
+            "name_1".to_string(),
+            Value::Timestamp("2026-03-13T12:00:00Z".parse().unwrap()),
+        )]);
+        assert_eq!(
+            template.render(&values, &entries),
+            "FROM t WHERE a = 'NaN'::float AND b = 'it\\'s' \
+             AND c = '2026-03-13T12:00:00Z'::timestamp AND d = $0 \
+             AND e = '$1' -- $1\n AND f = $other"
+        );
+    }
+
+    #[test]
+    fn test_render_negative() {
+        let template = Template::parse("SELECT a-$1, b-$2\nFROM t".to_string());
+        let values = [Value::Int(-1), Value::Float(-1.5)];
+        assert_eq!(
+            template.render(&values, &BTreeMap::new()),
+            "SELECT a-(-1), b-(-1.5::float)\nFROM t"
+        );
+    }
+
+    #[test]
+    fn test_literal() {
+        let cases = [
+            (Value::Int(1), "1"),
+            (Value::Int(-1), "(-1)"),
+            (Value::UInt(u64::MAX), "18446744073709551615::uint"),
+            (Value::Float(1.5), "1.5::float"),
+            (Value::Float(-1.5), "(-1.5::float)"),
+            (Value::Float(-0.0), "(-0.0::float)"),
+            (Value::Decimal("1.50".parse().unwrap()), "'1.50'::decimal"),
+            (
+                Value::Interval(jiff::Span::new().days(1)),
+                "'P1D'::interval",
+            ),
+            (Value::Boolean(true), "true"),
+            (Value::Binary("beef".to_string()), "'beef'::binary"),
+            (
+                Value::Array(r#"["a"]"#.to_string()),
+                r#"parse_json('["a"]')::array"#,
+            ),
+            (Value::Object("{}".to_string()), "parse_json('{}')::object"),
+            (Value::Null, "NULL"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(literal(&value), expected);
+        }
+    }
+}
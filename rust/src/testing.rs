@@ -0,0 +1,75 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fixtures shared by tests that run against a mock server.
+
+use serde_json::json;
+
+pub const STATEMENT_ID: &str = "01970000-0000-7000-8000-000000000000";
+
+pub fn progress() -> serde_json::Value {
+    json!({
+        "total_percentage": 100.0,
+        "nanos_from_submitted": 1_000_000,
+        "nanos_from_started": 500_000,
+        "total_stages": 1,
+        "total_partitions": 1,
+        "total_rows": 1,
+        "total_compressed_bytes": 0,
+        "total_uncompressed_bytes": 0,
+        "scanned_stages": 1,
+        "scanned_partitions": 1,
+        "scanned_rows": 1,
+        "scanned_compressed_bytes": 0,
+        "scanned_uncompressed_bytes": 0,
+        "skipped_partitions": 0,
+        "skipped_rows": 0,
+        "skipped_compressed_bytes": 0,
+        "skipped_uncompressed_bytes": 0,
+    })
+}
+
+pub fn status(status: &str) -> serde_json::Value {
+    json!({
+        "status": status,
+        "statement_id": STATEMENT_ID,
+        "created_at": "2026-03-13T12:00:00Z",
+        "progress": progress(),
+    })
+}
+
+pub fn finished(result_set: serde_json::Value) -> serde_json::Value {
+    let mut status = status("finished");
+    status["result_set"] = result_set;
+    status
+}
+
+pub fn failed(message: &str) -> serde_json::Value {
+    let mut status = status("failed");
+    status["message"] = json!(message);
+    status
+}
+
+pub fn result_set(fields: &[(&str, &str)], rows: serde_json::Value) -> serde_json::Value {
+    let fields = fields
+        .iter()
+        .map(|(name, data_type)| json!({"name": name, "data_type": data_type}))
+        .collect::<Vec<_>>();
+    let num_rows = rows.as_array().map_or(0, Vec::len);
+    json!({
+        "metadata": {"fields": fields, "num_rows": num_rows},
+        "format": "json",
+        "rows": rows,
+    })
+}
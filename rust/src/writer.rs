@@ -0,0 +1,349 @@
+// Copyright 2024 ScopeDB, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::task::Context;
+use std::task::Poll;
+use std::task::ready;
+
+use futures::Sink;
+use serde::Serialize;
+
+use crate::BoxFuture;
+use crate::Error;
+use crate::ErrorKind;
+use crate::IngestResult;
+use crate::IngestStream;
+
+/// A buffered writer of rows into ScopeDB, e.g., to ship application events without batching them
+/// by hand.
+///
+/// Rows are serialized as JSON objects and buffered, and a background task inserts them through
+/// the transform statement whenever the buffer reaches the batch size or the flush interval
+/// elapses. [`write`](Self::write) waits while the rows not yet inserted exceed the pending byte
+/// budget, so a writer that outpaces the server slows down instead of growing without bound.
+///
+/// Create a writer with [`Client::writer`], or from an [`IngestStream`] to tune its batch size,
+/// flush interval, pending byte budget, and retries. Once a flush fails for good, every later
+/// call fails with its error.
+///
+/// A writer can be shared across tasks, e.g., in an [`Arc`]. Use [`sink`](Self::sink) to write a
+/// stream of JSON values into it as a [`Sink`].
+///
+/// [`Client::writer`]: crate::Client::writer
+pub struct Writer {
+    stream: Arc<IngestStream>,
+}
+
+impl From<IngestStream> for Writer {
+    fn from(stream: IngestStream) -> Self {
+        Self {
+            stream: Arc::new(stream),
+        }
+    }
+}
+
+impl Writer {
+    /// Buffer a row, which must serialize into a JSON object.
+    pub async fn write<T: Serialize>(&self, row: &T) -> Result<(), Error> {
+        self.stream.send(row).await
+    }
+
+    /// Insert the buffered rows now.
+    pub async fn flush(&self) -> Result<(), Error> {
+        self.stream.flush().await.map(|_| ())
+    }
+
+    /// Insert the buffered rows and stop the background task.
+    ///
+    /// Returns the number of rows inserted over the lifetime of the writer.
+    pub async fn close(self) -> Result<IngestResult, Error> {
+        self.stream.close().await?;
+        Ok(IngestResult {
+            num_rows_inserted: self.stream.num_rows_inserted.load(Ordering::Acquire),
+        })
+    }
+
+    /// A [`Sink`] of JSON values that writes into this writer, e.g., `stream.forward(sink)`
+    /// writes every item of a stream and closes the writer at its end.
+    ///
+    /// Each value must be a JSON object. The sink writes its values in order; writes through the
+    /// writer or another sink may interleave with them. Flushing the sink inserts the buffered
+    /// rows like [`flush`](Self::flush), and closing it stops the writer like
+    /// [`close`](Self::close).
+    pub fn sink(&self) -> WriterSink {
+        WriterSink {
+            stream: self.stream.clone(),
+            pending: None,
+        }
+    }
+}
+
+/// A [`Sink`] of JSON values into a [`Writer`], created by [`Writer::sink`].
+pub struct WriterSink {
+    stream: Arc<IngestStream>,
+    pending: Option<(SinkOp, BoxFuture<'static, Result<(), Error>>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SinkOp {
+    Write,
+    Flush,
+    Close,
+}
+
+impl WriterSink {
+    /// Drive the pending sink operation, if any, and then `op`, to completion.
+    fn poll_op(&mut self, cx: &mut Context<'_>, op: SinkOp) -> Poll<Result<(), Error>> {
+        loop {
+            match &mut self.pending {
+                Some((pending_op, pending)) => {
+                    let pending_op = *pending_op;
+                    let result = ready!(pending.as_mut().poll(cx));
+                    self.pending = None;
+                    if pending_op == op || result.is_err() {
+                        return Poll::Ready(result);
+                    }
+                }
+                None => {
+                    let stream = self.stream.clone();
+                    let pending: BoxFuture<'static, Result<(), Error>> = match op {
+                        SinkOp::Write => return Poll::Ready(Ok(())),
+                        SinkOp::Flush => Box::pin(async move { stream.flush().await.map(|_| ()) }),
+                        SinkOp::Close => Box::pin(async move { stream.close().await.map(|_| ()) }),
+                    };
+                    self.pending = Some((op, pending));
+                }
+            }
+        }
+    }
+
+    fn start_write(&mut self, payload: String) {
+        let stream = self.stream.clone();
+        let pending = Box::pin(async move { stream.send_payload(payload).await });
+        self.pending = Some((SinkOp::Write, pending));
+    }
+}
+
+impl Sink<serde_json::Value> for WriterSink {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_op(cx, SinkOp::Write)
+    }
+
+    fn start_send(self: Pin<&mut Self>, row: serde_json::Value) -> Result<(), Error> {
+        if !row.is_object() {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                format!("expected JSON object for ingest row, got {row}"),
+            ));
+        }
+        self.get_mut().start_write(row.to_string());
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_op(cx, SinkOp::Flush)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_op(cx, SinkOp::Close)
+    }
+}
+
+/// A [`Sink`] of record batches, each inserted through the transform statement as Arrow ingest
+/// data, created by [`Client::arrow_sink`].
+///
+/// Batches are inserted one request at a time, in order, and the transform statement refers to
+/// their columns by name. Once an insert fails, the sink fails with its error.
+///
+/// [`Client::arrow_sink`]: crate::Client::arrow_sink
+#[cfg(feature = "arrow")]
+pub struct ArrowSink {
+    client: crate::Client,
+    transform: String,
+    pending: Option<BoxFuture<'static, Result<IngestResult, Error>>>,
+    num_rows_inserted: i64,
+}
+
+#[cfg(feature = "arrow")]
+impl ArrowSink {
+    pub(crate) fn new(client: crate::Client, transform: String) -> Self {
+        Self {
+            client,
+            transform,
+            pending: None,
+            num_rows_inserted: 0,
+        }
+    }
+
+    /// The number of rows inserted so far.
+    pub fn num_rows_inserted(&self) -> i64 {
+        self.num_rows_inserted
+    }
+
+    /// Drive the pending insert, if any, to completion.
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        if let Some(pending) = &mut self.pending {
+            let result = ready!(pending.as_mut().poll(cx));
+            self.pending = None;
+            self.num_rows_inserted += result?.num_rows_inserted;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl Sink<arrow_array::RecordBatch> for ArrowSink {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, batch: arrow_array::RecordBatch) -> Result<(), Error> {
+        let this = self.get_mut();
+        let data = crate::IngestData::from_record_batches(&[batch])?;
+        let client = this.client.clone();
+        let transform = this.transform.clone();
+        this.pending = Some(Box::pin(async move {
+            client.insert_data(data, transform).await
+        }));
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.get_mut().poll_pending(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::SinkExt;
+    use futures::StreamExt;
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_partial_json;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    use super::*;
+    use crate::Client;
+
+    async fn mock_ingest(server: &MockServer, rows: &str, num_rows: i64) {
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(body_partial_json(json!({
+                "data": {"format": "json", "rows": rows},
+                "statement": "INSERT INTO t",
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": num_rows})),
+            )
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    fn writer(client: &Client) -> Writer {
+        Writer::from(
+            client
+                .ingest_stream("INSERT INTO t")
+                .flush_interval(Duration::from_secs(3600))
+                .build(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_writer() {
+        let server = MockServer::start().await;
+        mock_ingest(&server, "{\"k\":1}\n{\"k\":2}", 2).await;
+        mock_ingest(&server, "{\"k\":3}", 1).await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let writer = Arc::new(writer(&client));
+        writer.write(&json!({"k": 1})).await.unwrap();
+        // A shared writer can be used from other tasks.
+        let shared = writer.clone();
+        tokio::spawn(async move { shared.write(&json!({"k": 2})).await })
+            .await
+            .unwrap()
+            .unwrap();
+        writer.flush().await.unwrap();
+        writer.write(&json!({"k": 3})).await.unwrap();
+        let result = Arc::into_inner(writer).unwrap().close().await.unwrap();
+        assert_eq!(result.num_rows_inserted, 3);
+    }
+
+    #[tokio::test]
+    async fn test_sink() {
+        let server = MockServer::start().await;
+        mock_ingest(&server, "{\"k\":1}\n{\"k\":2}", 2).await;
+        mock_ingest(&server, "{\"k\":3}", 1).await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let writer = Arc::new(writer(&client));
+        let mut sink = writer.sink();
+        let rows = futures::stream::iter([json!({"k": 1}), json!({"k": 2})]);
+        sink.send_all(&mut rows.map(Ok)).await.unwrap();
+        assert!(sink.feed(json!([4])).await.is_err());
+        sink.feed(json!({"k": 3})).await.unwrap();
+        sink.close().await.unwrap();
+        assert_eq!(writer.stream.num_rows_inserted.load(Ordering::Acquire), 3);
+        assert!(writer.write(&json!({"k": 4})).await.is_err());
+    }
+
+    #[cfg(feature = "arrow")]
+    #[tokio::test]
+    async fn test_record_batch_sink() {
+        use arrow_array::Int64Array;
+        use arrow_array::RecordBatch;
+        use arrow_array::StringArray;
+
+        let batch = RecordBatch::try_from_iter([
+            ("k", Arc::new(Int64Array::from(vec![1, 2])) as _),
+            ("v", Arc::new(StringArray::from(vec![Some("a"), None])) as _),
+        ])
+        .unwrap();
+        let rows = crate::arrow::encode_batches(&[batch.clone()]).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/ingest"))
+            .and(body_partial_json(json!({
+                "data": {"format": "arrow", "rows": rows},
+                "statement": "INSERT INTO t",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"num_rows_inserted": 2})))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder(server.uri()).build().unwrap();
+        let mut sink = client.arrow_sink("INSERT INTO t");
+        let mut batches = futures::stream::iter([Ok(batch.clone()), Ok(batch)]);
+        sink.send_all(&mut batches).await.unwrap();
+        assert_eq!(sink.num_rows_inserted(), 4);
+    }
+}